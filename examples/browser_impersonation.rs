@@ -14,7 +14,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .impersonate(Browser::ChromeLatest)
         .send()?;
 
-    println!("Response: {}\n", String::from_utf8_lossy(&response));
+    println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
 
     // Test with specific Chrome version
     println!("2. Impersonating Chrome 110:");
@@ -22,7 +22,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .impersonate(Browser::Chrome { version: 110 })
         .send()?;
 
-    println!("Response: {}\n", String::from_utf8_lossy(&response));
+    println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
 
     // Test with Firefox
     println!("3. Impersonating Firefox (latest):");
@@ -30,7 +30,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .impersonate(Browser::FirefoxLatest)
         .send()?;
 
-    println!("Response: {}\n", String::from_utf8_lossy(&response));
+    println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
 
     // Test with Safari
     println!("4. Impersonating Safari (latest):");
@@ -38,7 +38,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .impersonate(Browser::SafariLatest)
         .send()?;
 
-    println!("Response: {}\n", String::from_utf8_lossy(&response));
+    println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
 
     // Real-world example: TLS fingerprint test
     println!("5. Testing TLS fingerprint with Chrome:");
@@ -47,7 +47,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()?;
 
     println!("TLS Fingerprint Response (first 500 chars):");
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     println!("{}\n", body.chars().take(500).collect::<String>());
 
     Ok(())
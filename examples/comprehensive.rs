@@ -35,11 +35,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 fn simple_requests() -> Result<(), Box<dyn std::error::Error>> {
     // Simple GET
     let response = Request::get("https://httpbin.org/get").send()?;
-    println!("GET response: {} bytes", response.len());
+    println!("GET response: {} bytes", response.bytes().len());
 
     // Simple POST
     let response = Request::post("https://httpbin.org/post", r#"{"key": "value"}"#).send()?;
-    println!("POST response: {} bytes", response.len());
+    println!("POST response: {} bytes", response.bytes().len());
 
     Ok(())
 }
@@ -49,25 +49,25 @@ fn browser_impersonation() -> Result<(), Box<dyn std::error::Error>> {
     let response = Request::get("https://httpbin.org/headers")
         .impersonate(Browser::ChromeLatest)
         .send()?;
-    println!("Chrome impersonation: {} bytes", response.len());
+    println!("Chrome impersonation: {} bytes", response.bytes().len());
 
     // Specific Chrome version
     let response = Request::get("https://httpbin.org/headers")
         .impersonate(Browser::Chrome { version: 110 })
         .send()?;
-    println!("Chrome 110: {} bytes", response.len());
+    println!("Chrome 110: {} bytes", response.bytes().len());
 
     // Firefox
     let response = Request::get("https://httpbin.org/headers")
         .impersonate(Browser::FirefoxLatest)
         .send()?;
-    println!("Firefox: {} bytes", response.len());
+    println!("Firefox: {} bytes", response.bytes().len());
 
     // Safari
     let response = Request::get("https://httpbin.org/headers")
         .impersonate(Browser::SafariLatest)
         .send()?;
-    println!("Safari: {} bytes", response.len());
+    println!("Safari: {} bytes", response.bytes().len());
 
     Ok(())
 }
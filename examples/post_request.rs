@@ -10,14 +10,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let data = r#"{"name": "curl-cffi-rs", "type": "library"}"#;
     let response = post("https://httpbin.org/post", data)?;
 
-    println!("Response length: {} bytes\n", response.len());
+    println!("Response length: {} bytes\n", response.bytes().len());
 
     // Method 2: POST with Request builder
     println!("2. POST with Request builder:");
     let data = r#"{"message": "Hello from Rust!"}"#;
     let response = Request::post("https://httpbin.org/post", data).send()?;
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     println!(
         "Response (first 500 chars):\n{}\n",
         body.chars().take(500).collect::<String>()
@@ -30,7 +30,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .impersonate(Browser::ChromeLatest)
         .send()?;
 
-    println!("Response length: {} bytes\n", response.len());
+    println!("Response length: {} bytes\n", response.bytes().len());
 
     // Method 4: POST with impersonation and proxy (will fail without proxy)
     println!("4. POST with impersonation + proxy:");
@@ -41,7 +41,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
     {
         Ok(response) => {
-            println!("Response length: {} bytes\n", response.len());
+            println!("Response length: {} bytes\n", response.bytes().len());
         }
         Err(e) => {
             println!("Failed (expected without proxy): {}\n", e);
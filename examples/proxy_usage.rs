@@ -15,7 +15,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
     {
         Ok(response) => {
-            println!("Response: {}\n", String::from_utf8_lossy(&response));
+            println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
         }
         Err(e) => {
             println!("Failed (expected if no proxy): {}\n", e);
@@ -30,7 +30,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
     {
         Ok(response) => {
-            println!("Response: {}\n", String::from_utf8_lossy(&response));
+            println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
         }
         Err(e) => {
             println!("Failed (expected if no proxy): {}\n", e);
@@ -45,7 +45,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .send()
     {
         Ok(response) => {
-            println!("Response: {}\n", String::from_utf8_lossy(&response));
+            println!("Response: {}\n", String::from_utf8_lossy(response.bytes()));
         }
         Err(e) => {
             println!("Failed (expected if no proxy): {}\n", e);
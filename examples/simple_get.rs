@@ -8,13 +8,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Method 1: Using convenience function
     println!("1. Using convenience function:");
     let response = get("https://httpbin.org/get")?;
-    println!("Response length: {} bytes\n", response.len());
+    println!("Response length: {} bytes\n", response.bytes().len());
 
     // Method 2: Using Request builder
     println!("2. Using Request builder:");
     let response = Request::get("https://httpbin.org/get").send()?;
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     println!("Response:\n{}\n", body);
 
     Ok(())
@@ -0,0 +1,135 @@
+//! Aborting a running transfer from another thread
+
+use std::os::raw::{c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Stops a transfer that is running on another thread
+///
+/// Get one from [`Curl::abort_handle`](crate::Curl::abort_handle) or
+/// [`Request::abort_handle`](crate::Request::abort_handle) before starting
+/// the transfer. [`abort`](Self::abort) makes the blocking call return
+/// [`CurlError::Cancelled`](crate::CurlError::Cancelled) within about a
+/// second, or right away while data is moving. Clones refer to the same
+/// handle, and one handle can be given to any number of transfers.
+///
+/// An aborted handle stays aborted: transfers started with it afterwards
+/// fail right away.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{CurlError, Request};
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let mut request = Request::get("https://example.com/large-file");
+/// let abort = request.abort_handle();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(5));
+///     abort.abort();
+/// });
+/// match request.send() {
+///     Err(CurlError::Cancelled) => println!("gave up"),
+///     other => println!("{:?}", other.map(|r| r.status())),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AbortHandle {
+    aborted: Arc<AtomicBool>,
+}
+
+impl AbortHandle {
+    /// Create a handle that hasn't been aborted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop every transfer using this handle
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::Release);
+    }
+
+    /// Whether [`abort`](Self::abort) was called
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(feature = "python")]
+thread_local! {
+    /// Extra reason to abort transfers on this thread, checked from the
+    /// progress callback
+    static INTERRUPT_CHECK: std::cell::Cell<Option<fn() -> bool>> = const { std::cell::Cell::new(None) };
+}
+
+/// Run `f` with transfers on this thread also aborting when `check` returns
+/// `true`
+///
+/// Only transfers with an [`AbortHandle`] run the check, and aborting one
+/// this way aborts its handle.
+#[cfg(feature = "python")]
+pub(crate) fn with_interrupt_check<T>(check: fn() -> bool, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<fn() -> bool>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            INTERRUPT_CHECK.with(|slot| slot.set(self.0));
+        }
+    }
+
+    let _restore = Restore(INTERRUPT_CHECK.with(|slot| slot.replace(Some(check))));
+    f()
+}
+
+/// `CURLOPT_XFERINFOFUNCTION`: a non-zero return aborts the transfer with
+/// `CURLE_ABORTED_BY_CALLBACK`
+pub(crate) extern "C" fn xferinfo_func(
+    clientp: *mut c_void,
+    _dltotal: curl_sys::curl_off_t,
+    _dlnow: curl_sys::curl_off_t,
+    _ultotal: curl_sys::curl_off_t,
+    _ulnow: curl_sys::curl_off_t,
+) -> c_int {
+    let abort = unsafe { &*(clientp as *const AbortHandle) };
+    should_stop(abort) as c_int
+}
+
+/// Whether work using `abort` should stop: it was aborted, or the thread's
+/// interrupt check fired, which aborts it
+pub(crate) fn should_stop(abort: &AbortHandle) -> bool {
+    if abort.is_aborted() {
+        return true;
+    }
+    #[cfg(feature = "python")]
+    if INTERRUPT_CHECK
+        .with(|slot| slot.get())
+        .is_some_and(|check| check())
+    {
+        abort.abort();
+        return true;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Curl, CurlError};
+
+    #[test]
+    fn test_aborted_handle_fails_fast() {
+        let mut curl = Curl::new().unwrap();
+        curl.set_url("http://127.0.0.1:1/").unwrap();
+        curl.abort_handle().unwrap().abort();
+        assert!(curl.abort_handle().unwrap().is_aborted());
+        assert!(matches!(
+            curl.perform(&mut Vec::new()),
+            Err(CurlError::Cancelled)
+        ));
+
+        curl.reset();
+        curl.set_url("http://127.0.0.1:1/").unwrap();
+        assert!(matches!(
+            curl.perform(&mut Vec::new()),
+            Err(CurlError::CurlCode { .. })
+        ));
+    }
+}
@@ -0,0 +1,272 @@
+//! Fetching many requests with bounded concurrency on one thread
+
+use crate::error::{CurlError, Result};
+use crate::multi::{Multi, TransferId, POLL_TIMEOUT};
+use crate::response::Response;
+use crate::Request;
+use std::collections::{HashMap, VecDeque};
+
+/// Default number of requests in flight at once
+pub const DEFAULT_CONCURRENCY: usize = 10;
+
+/// Limits for [`batch`] and [`batch_iter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// Requests in flight at once; 0 counts as 1
+    pub concurrency: usize,
+    /// Requests in flight to any one host at once; 0 for no limit
+    pub per_host_limit: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            concurrency: DEFAULT_CONCURRENCY,
+            per_host_limit: 0,
+        }
+    }
+}
+
+/// Send all requests, a few at a time, and return their results in input
+/// order
+///
+/// Runs on the calling thread through one [`Multi`], so connections are
+/// reused between requests to the same host. A failed request doesn't stop
+/// the others.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{batch, BatchOptions, Browser, Request};
+///
+/// let requests = (0..1000).map(|i| {
+///     Request::get(format!("https://example.com/item/{}", i)).impersonate(Browser::ChromeLatest)
+/// });
+/// let options = BatchOptions {
+///     concurrency: 50,
+///     per_host_limit: 10,
+/// };
+/// for (i, result) in batch(requests, options).into_iter().enumerate() {
+///     match result {
+///         Ok(response) => println!("{}: {}", i, response.status()),
+///         Err(e) => println!("{} failed: {}", i, e),
+///     }
+/// }
+/// ```
+pub fn batch<I>(requests: I, options: BatchOptions) -> Vec<Result<Response>>
+where
+    I: IntoIterator<Item = Request>,
+{
+    let batch = batch_iter(requests, options);
+    let mut results: Vec<Option<Result<Response>>> =
+        std::iter::repeat_with(|| None).take(batch.len()).collect();
+    for (index, result) in batch {
+        results[index] = Some(result);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every request yields a result"))
+        .collect()
+}
+
+/// Like [`batch`], but yield `(index, result)` pairs as requests finish
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{batch_iter, BatchOptions, Request};
+///
+/// let urls = ["https://example.com/a", "https://example.org/b"];
+/// let requests = urls.iter().map(|url| Request::get(*url));
+/// for (index, result) in batch_iter(requests, BatchOptions::default()) {
+///     println!("{} finished: {}", urls[index], result.is_ok());
+/// }
+/// ```
+pub fn batch_iter<I>(requests: I, options: BatchOptions) -> Batch
+where
+    I: IntoIterator<Item = Request>,
+{
+    Batch {
+        multi: None,
+        pending: requests.into_iter().enumerate().collect(),
+        waiting: HashMap::new(),
+        waiting_count: 0,
+        running: HashMap::new(),
+        per_host: HashMap::new(),
+        finished: VecDeque::new(),
+        options,
+    }
+}
+
+/// Iterator over the results of [`batch_iter`], in the order requests
+/// finish
+pub struct Batch {
+    /// Created with the first request
+    multi: Option<Multi>,
+    /// Requests not looked at yet, in input order
+    pending: VecDeque<(usize, Request)>,
+    /// Requests held back by the per-host limit, by host
+    waiting: HashMap<String, VecDeque<(usize, Request)>>,
+    waiting_count: usize,
+    /// Input index and host of each transfer in flight
+    running: HashMap<TransferId, (usize, String)>,
+    per_host: HashMap<String, usize>,
+    finished: VecDeque<(usize, Result<Response>)>,
+    options: BatchOptions,
+}
+
+impl Batch {
+    /// Start requests until the concurrency limit is reached
+    fn fill(&mut self) -> Result<()> {
+        let concurrency = self.options.concurrency.max(1);
+        while self.running.len() < concurrency {
+            let Some((index, request)) = self.pending.pop_front() else {
+                break;
+            };
+            // A URL that can't be parsed fails when the transfer is added
+            let host = crate::url::host(&request.url)
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let limit = self.options.per_host_limit;
+            if limit > 0 && self.per_host.get(&host).copied().unwrap_or(0) >= limit {
+                self.waiting
+                    .entry(host)
+                    .or_default()
+                    .push_back((index, request));
+                self.waiting_count += 1;
+                continue;
+            }
+            self.start(index, request, host)?;
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, index: usize, request: Request, host: String) -> Result<()> {
+        let multi = match &mut self.multi {
+            Some(multi) => multi,
+            None => match Multi::new() {
+                Ok(multi) => self.multi.insert(multi),
+                Err(e) => {
+                    let message = e.to_string();
+                    self.finished.push_back((index, Err(e)));
+                    return Err(CurlError::Other(message));
+                }
+            },
+        };
+        match multi.add_request(request) {
+            Ok(id) => {
+                *self.per_host.entry(host.clone()).or_default() += 1;
+                self.running.insert(id, (index, host));
+            }
+            Err(e) => self.finished.push_back((index, Err(e))),
+        }
+        Ok(())
+    }
+
+    /// Drive the transfers once and queue the ones that finished
+    fn step(&mut self) -> Result<()> {
+        let Some(multi) = &mut self.multi else {
+            return Ok(());
+        };
+        multi.perform()?;
+
+        let mut done = Vec::new();
+        while let Some(completed) = multi.next_completed() {
+            done.push(completed);
+        }
+        if done.is_empty() {
+            return multi.poll(POLL_TIMEOUT);
+        }
+
+        for completed in done {
+            let Some((index, host)) = self.running.remove(&completed.id) else {
+                continue;
+            };
+            self.finished.push_back((index, completed.result));
+            if let Some(count) = self.per_host.get_mut(&host) {
+                *count -= 1;
+                if *count == 0 {
+                    self.per_host.remove(&host);
+                }
+            }
+            // Requests held back for this host were before anything pending
+            let next = self.waiting.get_mut(&host).and_then(VecDeque::pop_front);
+            if self.waiting.get(&host).is_some_and(VecDeque::is_empty) {
+                self.waiting.remove(&host);
+            }
+            if let Some((index, request)) = next {
+                self.waiting_count -= 1;
+                self.start(index, request, host)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The multi handle failed: every request left fails with it
+    fn fail_all(&mut self, error: CurlError) {
+        let message = error.to_string();
+        let mut unfinished: Vec<usize> =
+            self.running.drain().map(|(_, (index, _))| index).collect();
+        unfinished.extend(self.pending.drain(..).map(|(index, _)| index));
+        unfinished.extend(
+            self.waiting
+                .drain()
+                .flat_map(|(_, requests)| requests.into_iter().map(|(index, _)| index)),
+        );
+        self.waiting_count = 0;
+        self.multi = None;
+        unfinished.sort_unstable();
+        for index in unfinished {
+            self.finished
+                .push_back((index, Err(CurlError::Other(message.clone()))));
+        }
+    }
+}
+
+impl Iterator for Batch {
+    type Item = (usize, Result<Response>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.finished.pop_front() {
+                return Some(item);
+            }
+            if self.running.is_empty() && self.pending.is_empty() && self.waiting_count == 0 {
+                return None;
+            }
+            if let Err(e) = self.fill().and_then(|()| self.step()) {
+                self.fail_all(e);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len =
+            self.finished.len() + self.running.len() + self.pending.len() + self.waiting_count;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Batch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_in_input_order() {
+        let requests = vec![
+            Request::get("http://127.0.0.1:1/a"),
+            Request::get("bad\0url"),
+            Request::get("http://127.0.0.1:1/c"),
+        ];
+        let options = BatchOptions {
+            concurrency: 2,
+            per_host_limit: 1,
+        };
+        let results = batch(requests, options);
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Err(CurlError::CurlCode { .. })));
+        assert!(matches!(results[1], Err(CurlError::InvalidUrl(_))));
+        assert!(matches!(results[2], Err(CurlError::CurlCode { .. })));
+    }
+}
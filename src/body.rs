@@ -0,0 +1,169 @@
+//! Streaming request bodies fed to libcurl through `CURLOPT_READFUNCTION`
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::os::raw::{c_char, c_int, c_void};
+
+/// `Read + Seek` as a single object-safe trait
+pub(crate) trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// Where a streamed body comes from
+pub(crate) enum BodySource {
+    /// Forward-only reader; libcurl can't rewind it
+    Plain(Box<dyn Read + Send>),
+    /// Reader that can be rewound for redirects and auth retries
+    Seekable(Box<dyn ReadSeek + Send>),
+}
+
+impl BodySource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            BodySource::Plain(reader) => reader.read(buf),
+            BodySource::Seekable(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Request body held by the `Request` builder until it is applied to a handle
+pub(crate) enum RequestBody {
+    Bytes(Vec<u8>),
+    Stream(BodySource, Option<u64>),
+    /// Pairs encoded as `application/x-www-form-urlencoded` when applied
+    Form(Vec<(String, String)>),
+    Multipart(crate::multipart::Multipart),
+}
+
+/// State shared with the read and seek callbacks
+///
+/// Boxed by the owning `Curl` so the pointer passed as `CURLOPT_READDATA`
+/// stays valid for the lifetime of the transfer.
+pub(crate) struct BodyReader {
+    source: BodySource,
+    /// First error returned by the reader, reported once the transfer aborts
+    error: Option<io::Error>,
+}
+
+impl BodyReader {
+    pub(crate) fn new(source: BodySource) -> Self {
+        BodyReader {
+            source,
+            error: None,
+        }
+    }
+
+    pub(crate) fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Seek back to the start so the body can be sent again
+    ///
+    /// Returns `false` for a forward-only reader.
+    pub(crate) fn rewind(&mut self) -> io::Result<bool> {
+        match &mut self.source {
+            BodySource::Plain(_) => Ok(false),
+            BodySource::Seekable(reader) => reader.seek(SeekFrom::Start(0)).map(|_| true),
+        }
+    }
+}
+
+pub(crate) extern "C" fn read_func(
+    buffer: *mut c_char,
+    size: usize,
+    nitems: usize,
+    userdata: *mut c_void,
+) -> usize {
+    // A null userdata means the handle has no streamed body: report EOF
+    if userdata.is_null() {
+        return 0;
+    }
+
+    let total_size = size * nitems;
+    let body = unsafe { &mut *(userdata as *mut BodyReader) };
+    let buf = unsafe { std::slice::from_raw_parts_mut(buffer as *mut u8, total_size) };
+
+    loop {
+        match body.source.read(buf) {
+            Ok(n) => return n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                body.error = Some(e);
+                return curl_sys::CURL_READFUNC_ABORT;
+            }
+        }
+    }
+}
+
+pub(crate) extern "C" fn seek_func(
+    userdata: *mut c_void,
+    offset: curl_sys::curl_off_t,
+    origin: c_int,
+) -> c_int {
+    if userdata.is_null() {
+        return curl_sys::CURL_SEEKFUNC_CANTSEEK;
+    }
+
+    let body = unsafe { &mut *(userdata as *mut BodyReader) };
+    let BodySource::Seekable(reader) = &mut body.source else {
+        return curl_sys::CURL_SEEKFUNC_CANTSEEK;
+    };
+
+    let pos = match origin {
+        libc::SEEK_SET => match u64::try_from(offset) {
+            Ok(offset) => SeekFrom::Start(offset),
+            Err(_) => return curl_sys::CURL_SEEKFUNC_FAIL,
+        },
+        libc::SEEK_CUR => SeekFrom::Current(offset),
+        libc::SEEK_END => SeekFrom::End(offset),
+        _ => return curl_sys::CURL_SEEKFUNC_FAIL,
+    };
+
+    match reader.seek(pos) {
+        Ok(_) => curl_sys::CURL_SEEKFUNC_OK,
+        Err(e) => {
+            body.error = Some(e);
+            curl_sys::CURL_SEEKFUNC_FAIL
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_func_reads_and_reports_eof() {
+        let mut body = BodyReader::new(BodySource::Plain(Box::new(Cursor::new(b"abc".to_vec()))));
+        let mut buf = [0u8; 8];
+        let userdata = &mut body as *mut BodyReader as *mut c_void;
+
+        let n = read_func(buf.as_mut_ptr() as *mut c_char, 1, buf.len(), userdata);
+        assert_eq!(&buf[..n], b"abc");
+        let n = read_func(buf.as_mut_ptr() as *mut c_char, 1, buf.len(), userdata);
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn test_seek_func_rewinds_seekable_only() {
+        let mut body =
+            BodyReader::new(BodySource::Seekable(Box::new(Cursor::new(b"abc".to_vec()))));
+        let mut buf = [0u8; 8];
+        let userdata = &mut body as *mut BodyReader as *mut c_void;
+
+        read_func(buf.as_mut_ptr() as *mut c_char, 1, buf.len(), userdata);
+        assert_eq!(
+            seek_func(userdata, 1, libc::SEEK_SET),
+            curl_sys::CURL_SEEKFUNC_OK
+        );
+        let n = read_func(buf.as_mut_ptr() as *mut c_char, 1, buf.len(), userdata);
+        assert_eq!(&buf[..n], b"bc");
+
+        let mut plain = BodyReader::new(BodySource::Plain(Box::new(io::empty())));
+        let userdata = &mut plain as *mut BodyReader as *mut c_void;
+        assert_eq!(
+            seek_func(userdata, 0, libc::SEEK_SET),
+            curl_sys::CURL_SEEKFUNC_CANTSEEK
+        );
+    }
+}
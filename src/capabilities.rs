@@ -0,0 +1,245 @@
+//! What the linked libcurl can do, read from `curl_version_info`
+
+use crate::types::{Browser, TlsVersion};
+use curl_sys::CURLoption;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::sync::OnceLock;
+
+/// Features of the libcurl hyprcurl runs on
+///
+/// These depend on how libcurl was built, not on hyprcurl, so impersonation
+/// checks them before promising a browser's TLS parameters, HTTP/2 or
+/// content encodings.
+///
+/// # Examples
+/// ```
+/// let caps = hyprcurl::capabilities();
+/// println!("libcurl {} with {:?}", caps.version, caps.ssl_backend);
+/// if !caps.http2 {
+///     println!("browser impersonation will fall back to HTTP/1.1");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// libcurl version, like `"8.17.0"`
+    pub version: String,
+    /// TLS library and its version, like `"OpenSSL/3.0.13"`, or `None`
+    /// without TLS support
+    pub ssl_backend: Option<String>,
+    pub http2: bool,
+    pub http3: bool,
+    /// zlib, for the `gzip` and `deflate` content encodings
+    pub libz: bool,
+    /// The TLS backend takes a TLS 1.2 cipher list
+    pub cipher_list: bool,
+    /// The TLS backend takes a separate TLS 1.3 cipher suite list
+    pub tls13_ciphers: bool,
+    /// The TLS backend takes a list of signature algorithms
+    pub signature_algorithms: bool,
+    /// libcurl-impersonate's options for the HTTP/2 SETTINGS, WINDOW_UPDATE,
+    /// PRIORITY frames and pseudo-header order
+    pub http2_fingerprint: bool,
+    /// libcurl-impersonate's `CURLOPT_SSL_SIG_HASH_ALGS`
+    pub sig_hash_algs: bool,
+    /// The `br` content encoding
+    pub brotli: bool,
+    /// The `zstd` content encoding
+    pub zstd: bool,
+    /// Internationalized domain names
+    pub idn: bool,
+    /// libcurl-impersonate's `curl_easy_impersonate`, used by
+    /// [`Curl::impersonate`](crate::Curl::impersonate)
+    pub impersonate: bool,
+    /// URL schemes libcurl speaks, in lowercase
+    pub protocols: Vec<String>,
+}
+
+impl Capabilities {
+    /// Whether libcurl speaks this URL scheme
+    pub fn supports_protocol(&self, scheme: &str) -> bool {
+        self.protocols
+            .iter()
+            .any(|protocol| protocol.eq_ignore_ascii_case(scheme))
+    }
+
+    /// Whether libcurl can decode responses with this `Content-Encoding`
+    pub fn supports_encoding(&self, encoding: &str) -> bool {
+        match encoding.to_ascii_lowercase().as_str() {
+            "identity" => true,
+            "gzip" | "x-gzip" | "deflate" => self.libz,
+            "br" => self.brotli,
+            "zstd" => self.zstd,
+            _ => false,
+        }
+    }
+
+    /// Whether the TLS backend can offer this key exchange group in
+    /// `CURLOPT_SSL_EC_CURVES`
+    ///
+    /// The OpenSSL family fails the whole handshake over a single unknown
+    /// name, and other backends ignore the option, so this errs on `false`.
+    pub fn supports_curve(&self, curve: &str) -> bool {
+        let Some((library, version)) = self.tls_library() else {
+            return false;
+        };
+        let openssl = matches!(library, "OpenSSL" | "quictls");
+        let boringssl = matches!(library, "BoringSSL" | "AWS-LC");
+        match curve {
+            "X25519" | "P-256" | "P-384" | "P-521" | "prime256v1" | "secp256r1" | "secp384r1"
+            | "secp521r1" => openssl || boringssl || matches!(library, "LibreSSL" | "wolfSSL"),
+            "X448" => openssl || library == "wolfSSL",
+            "X25519Kyber768Draft00" => boringssl,
+            "X25519MLKEM768" => boringssl || (openssl && version >= (3, 5)),
+            _ => false,
+        }
+    }
+
+    /// Whether the TLS backend offers this signature algorithm when it's
+    /// listed in `CURLOPT_SSL_SIGNATURE_ALGORITHMS` (or libcurl-impersonate's
+    /// `CURLOPT_SSL_SIG_HASH_ALGS`)
+    ///
+    /// OpenSSL 3 drops SHA-1 signatures at its default security level
+    /// without complaint.
+    pub fn supports_sigalg(&self, sigalg: &str) -> bool {
+        if !self.signature_algorithms && !self.sig_hash_algs {
+            return false;
+        }
+        match self.tls_library() {
+            Some(("OpenSSL" | "quictls", version)) if version >= (3, 0) => {
+                !sigalg.to_ascii_lowercase().ends_with("sha1")
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the TLS backend offers this version when it's inside the
+    /// range set with `CURLOPT_SSLVERSION`
+    ///
+    /// OpenSSL 3 leaves TLS 1.0 and 1.1 out at its default security level.
+    pub fn supports_tls_version(&self, version: TlsVersion) -> bool {
+        match self.tls_library() {
+            Some(("OpenSSL" | "quictls", openssl)) if openssl >= (3, 0) => {
+                version >= TlsVersion::V1_2
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Parts of `browser`'s fingerprint this libcurl can't reproduce, one
+    /// per option
+    ///
+    /// [`Curl::set_browser_impersonation`](crate::Curl::set_browser_impersonation)
+    /// leaves these out, and the strict variant fails over them. Useful to
+    /// check a [`Request`](crate::Request) or
+    /// [`Session`](crate::Session) impersonation before sending.
+    ///
+    /// # Examples
+    /// ```
+    /// use hyprcurl::Browser;
+    ///
+    /// for gap in hyprcurl::capabilities().impersonation_gaps(&Browser::FirefoxLatest) {
+    ///     eprintln!("Firefox impersonation without {}", gap);
+    /// }
+    /// ```
+    pub fn impersonation_gaps(&self, browser: &Browser) -> Vec<String> {
+        crate::curl::plan_browser(browser, self).gaps
+    }
+
+    /// Name and major and minor version of the TLS backend in use
+    fn tls_library(&self) -> Option<(&str, (u32, u32))> {
+        // A MultiSSL build lists the backends not in use in parentheses
+        let backend = self
+            .ssl_backend
+            .as_deref()?
+            .split_whitespace()
+            .find(|backend| !backend.starts_with('('))?;
+        let (library, version) = backend.split_once('/').unwrap_or((backend, ""));
+        let mut numbers = version.split('.').map(|n| n.parse().unwrap_or(0));
+        let major = numbers.next().unwrap_or(0);
+        let minor = numbers.next().unwrap_or(0);
+        Some((library, (major, minor)))
+    }
+}
+
+/// Features of the linked libcurl, read once
+pub fn capabilities() -> &'static Capabilities {
+    static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+    CAPABILITIES.get_or_init(read)
+}
+
+fn read() -> Capabilities {
+    crate::init_curl();
+    let info = unsafe { &*curl_sys::curl_version_info(curl_sys::CURLVERSION_NOW) };
+    let has = |feature| info.features & feature != 0;
+
+    let mut protocols = Vec::new();
+    if !info.protocols.is_null() {
+        let mut protocol = info.protocols;
+        unsafe {
+            while !(*protocol).is_null() {
+                protocols.push(string(*protocol).to_ascii_lowercase());
+                protocol = protocol.add(1);
+            }
+        }
+    }
+
+    Capabilities {
+        version: string(info.version),
+        ssl_backend: (!info.ssl_version.is_null()).then(|| string(info.ssl_version)),
+        http2: has(curl_sys::CURL_VERSION_HTTP2),
+        http3: has(curl_sys::CURL_VERSION_HTTP3),
+        libz: has(curl_sys::CURL_VERSION_LIBZ),
+        cipher_list: takes_option(curl_sys::CURLOPT_SSL_CIPHER_LIST, c"DEFAULT"),
+        tls13_ciphers: takes_option(crate::ffi::CURLOPT_TLS13_CIPHERS, c"TLS_AES_128_GCM_SHA256"),
+        signature_algorithms: takes_option(
+            crate::ffi::CURLOPT_SSL_SIGNATURE_ALGORITHMS,
+            c"rsa_pss_rsae_sha256",
+        ),
+        http2_fingerprint: takes_option(crate::ffi::CURLOPT_HTTP2_SETTINGS, c"1:65536"),
+        sig_hash_algs: takes_option(
+            crate::ffi::CURLOPT_SSL_SIG_HASH_ALGS,
+            c"rsa_pss_rsae_sha256",
+        ),
+        brotli: has(curl_sys::CURL_VERSION_BROTLI),
+        zstd: has(curl_sys::CURL_VERSION_ZSTD),
+        idn: has(curl_sys::CURL_VERSION_IDN),
+        impersonate: crate::impersonate::curl_easy_impersonate().is_some(),
+        protocols,
+    }
+}
+
+/// Whether libcurl accepts an option, which it refuses with
+/// `CURLE_NOT_BUILT_IN` when the TLS backend can't apply it, and with
+/// `CURLE_UNKNOWN_OPTION` when it isn't libcurl-impersonate's
+fn takes_option(option: CURLoption, value: &CStr) -> bool {
+    unsafe {
+        let handle = curl_sys::curl_easy_init();
+        if handle.is_null() {
+            return false;
+        }
+        let code = curl_sys::curl_easy_setopt(handle, option, value.as_ptr());
+        curl_sys::curl_easy_cleanup(handle);
+        code == curl_sys::CURLE_OK
+    }
+}
+
+fn string(ptr: *const c_char) -> String {
+    unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_match_version_string() {
+        let caps = capabilities();
+        assert!(crate::version().contains(&format!("libcurl/{}", caps.version)));
+        assert!(caps.supports_protocol("HTTP"));
+        assert!(caps.supports_encoding("identity"));
+        assert_eq!(caps.supports_encoding("gzip"), caps.libz);
+        assert!(!caps.supports_encoding("compress"));
+    }
+}
@@ -0,0 +1,753 @@
+//! Async requests on tokio, driven by a curl multi handle
+//!
+//! A [`Client`] owns one multi handle, run by a task on the tokio runtime.
+//! libcurl reports the sockets it waits on through `CURLMOPT_SOCKETFUNCTION`
+//! and its timeouts through `CURLMOPT_TIMERFUNCTION`; the task registers the
+//! sockets with tokio's reactor and calls `curl_multi_socket_action` for each
+//! one that becomes ready, so any number of transfers run without a thread
+//! of their own.
+
+use crate::error::{check_multi_code, CurlError, Result};
+use crate::response::Response;
+use crate::transfer::Transfer;
+use crate::types::Method;
+use crate::Request;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::os::raw::{c_int, c_long, c_short, c_void};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, Sleep};
+
+/// Async HTTP client running every request on one curl multi handle
+///
+/// Requests are sent with [`send`](Self::send) and take the same
+/// [`Request`] as the blocking API, impersonation included. Connections,
+/// the DNS cache and TLS sessions are shared by all requests of a client.
+/// Clones refer to the same client; the multi handle is closed once the
+/// last clone is dropped and its requests have finished.
+///
+/// Dropping the future returned by `send` aborts the request.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, Client, Request};
+///
+/// # async fn run() -> hyprcurl::Result<()> {
+/// let client = Client::new()?;
+///
+/// let tasks: Vec<_> = (0..100)
+///     .map(|i| {
+///         let client = client.clone();
+///         tokio::spawn(async move {
+///             let request = Request::get(format!("https://example.com/item/{}", i))
+///                 .impersonate(Browser::ChromeLatest);
+///             client.send(request).await
+///         })
+///     })
+///     .collect();
+/// for task in tasks {
+///     println!("{}", task.await.unwrap()?.status());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Client {
+    commands: mpsc::UnboundedSender<Command>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Client {
+    /// Create a client and start its driver task
+    ///
+    /// Must be called from within a tokio runtime with the I/O and time
+    /// drivers enabled.
+    pub fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| {
+            CurlError::Other("Client::new must be called within a tokio runtime".to_string())
+        })?;
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let driver = Driver::new(receiver)?;
+        runtime.spawn(driver);
+
+        Ok(Client {
+            commands,
+            next_id: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Send a request and wait for the whole response
+    pub async fn send(&self, request: Request) -> Result<Response> {
+        let transfer = Transfer::new(request.into_curl()?)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (done, response) = oneshot::channel();
+        self.commands
+            .send(Command::Start(Box::new(Running { id, transfer, done })))
+            .map_err(|_| driver_stopped())?;
+
+        let mut cancel = CancelOnDrop {
+            id,
+            commands: &self.commands,
+            armed: true,
+        };
+        let result = response.await.map_err(|_| driver_stopped());
+        cancel.armed = false;
+        result?
+    }
+
+    /// Send a GET request
+    pub async fn get(&self, url: impl Into<String>) -> Result<Response> {
+        self.send(Request::get(url)).await
+    }
+
+    /// Send a POST request with a body
+    pub async fn post(&self, url: impl Into<String>, data: impl Into<Vec<u8>>) -> Result<Response> {
+        self.send(Request::post(url, data)).await
+    }
+
+    /// Send a request with any method and no body
+    pub async fn request(
+        &self,
+        method: impl Into<Method>,
+        url: impl Into<String>,
+    ) -> Result<Response> {
+        self.send(Request::new(method, url)).await
+    }
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client").finish_non_exhaustive()
+    }
+}
+
+fn driver_stopped() -> CurlError {
+    CurlError::Other("the client's driver task has stopped".to_string())
+}
+
+/// Aborts the request when `Client::send` is dropped before it finished
+struct CancelOnDrop<'a> {
+    id: u64,
+    commands: &'a mpsc::UnboundedSender<Command>,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = self.commands.send(Command::Cancel(self.id));
+        }
+    }
+}
+
+enum Command {
+    Start(Box<Running>),
+    Cancel(u64),
+}
+
+/// What libcurl asked for from within `curl_multi_socket_action`
+///
+/// The callbacks only record requests; the driver applies them once the
+/// call has returned.
+#[derive(Default)]
+struct Wanted {
+    sockets: Vec<(curl_sys::curl_socket_t, c_int, *mut curl_sys::CURL)>,
+    /// New timeout in milliseconds, -1 to cancel it
+    timeout: Option<c_long>,
+}
+
+extern "C" fn socket_func(
+    easy: *mut curl_sys::CURL,
+    socket: curl_sys::curl_socket_t,
+    what: c_int,
+    userp: *mut c_void,
+    _socketp: *mut c_void,
+) -> c_int {
+    let wanted = unsafe { &mut *(userp as *mut Wanted) };
+    wanted.sockets.push((socket, what, easy));
+    0
+}
+
+extern "C" fn timer_func(
+    _multi: *mut curl_sys::CURLM,
+    timeout_ms: c_long,
+    userp: *mut c_void,
+) -> c_int {
+    let wanted = unsafe { &mut *(userp as *mut Wanted) };
+    wanted.timeout = Some(timeout_ms);
+    0
+}
+
+/// Socket libcurl owns and closes; registering it with tokio doesn't take
+/// ownership
+struct SocketFd(RawFd);
+
+impl AsRawFd for SocketFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+/// Sockets whose readiness may have changed, filled in by their wakers
+struct Woken {
+    sockets: Mutex<Vec<curl_sys::curl_socket_t>>,
+    driver: Mutex<Option<Waker>>,
+}
+
+/// Waker of one socket: queues the socket and wakes the driver, so a wake-up
+/// only looks at the sockets that are ready
+struct SocketWaker {
+    socket: curl_sys::curl_socket_t,
+    woken: Arc<Woken>,
+}
+
+impl Wake for SocketWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken
+            .sockets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(self.socket);
+        let driver = self
+            .woken
+            .driver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(driver) = driver {
+            driver.wake();
+        }
+    }
+}
+
+struct Socket {
+    fd: AsyncFd<SocketFd>,
+    /// `CURL_POLL_IN`, `CURL_POLL_OUT` or both
+    what: c_int,
+    waker: Waker,
+}
+
+struct Running {
+    id: u64,
+    transfer: Transfer,
+    done: oneshot::Sender<Result<Response>>,
+}
+
+/// Task running the multi handle
+struct Driver {
+    multi: *mut curl_sys::CURLM,
+    commands: mpsc::UnboundedReceiver<Command>,
+    /// Every `Client` is gone; stop once the last transfer finishes
+    closed: bool,
+    /// Keyed by easy handle
+    running: HashMap<usize, Running>,
+    handles: HashMap<u64, usize>,
+    sockets: HashMap<curl_sys::curl_socket_t, Socket>,
+    wanted: Box<Wanted>,
+    woken: Arc<Woken>,
+    timer: Pin<Box<Sleep>>,
+    timer_armed: bool,
+}
+
+// The multi handle and its easy handles are only used by the driver task
+unsafe impl Send for Driver {}
+
+impl Driver {
+    fn new(commands: mpsc::UnboundedReceiver<Command>) -> Result<Self> {
+        crate::init_curl();
+        let multi = unsafe { curl_sys::curl_multi_init() };
+        if multi.is_null() {
+            return Err(CurlError::InitError);
+        }
+
+        let mut driver = Driver {
+            multi,
+            commands,
+            closed: false,
+            running: HashMap::new(),
+            handles: HashMap::new(),
+            sockets: HashMap::new(),
+            wanted: Box::default(),
+            woken: Arc::new(Woken {
+                sockets: Mutex::new(Vec::new()),
+                driver: Mutex::new(None),
+            }),
+            timer: Box::pin(tokio::time::sleep(Duration::ZERO)),
+            timer_armed: false,
+        };
+
+        let socket: curl_sys::curl_socket_callback = socket_func;
+        let timer: curl_sys::curl_multi_timer_callback = timer_func;
+        let wanted = &mut *driver.wanted as *mut Wanted as *mut c_void;
+        unsafe {
+            check_multi_code(curl_sys::curl_multi_setopt(
+                multi,
+                curl_sys::CURLMOPT_SOCKETFUNCTION,
+                socket,
+            ))?;
+            check_multi_code(curl_sys::curl_multi_setopt(
+                multi,
+                curl_sys::CURLMOPT_SOCKETDATA,
+                wanted,
+            ))?;
+            check_multi_code(curl_sys::curl_multi_setopt(
+                multi,
+                curl_sys::CURLMOPT_TIMERFUNCTION,
+                timer,
+            ))?;
+            check_multi_code(curl_sys::curl_multi_setopt(
+                multi,
+                curl_sys::CURLMOPT_TIMERDATA,
+                wanted,
+            ))?;
+        }
+        Ok(driver)
+    }
+
+    fn command(&mut self, command: Command) {
+        match command {
+            Command::Start(running) => {
+                let handle = running.transfer.raw_handle();
+                if let Err(e) =
+                    check_multi_code(unsafe { curl_sys::curl_multi_add_handle(self.multi, handle) })
+                {
+                    let _ = running.done.send(Err(e));
+                    return;
+                }
+                self.handles.insert(running.id, handle as usize);
+                self.running.insert(handle as usize, *running);
+                self.apply_wanted();
+            }
+            Command::Cancel(id) => {
+                if let Some(handle) = self.handles.remove(&id) {
+                    unsafe {
+                        curl_sys::curl_multi_remove_handle(
+                            self.multi,
+                            handle as *mut curl_sys::CURL,
+                        );
+                    }
+                    self.running.remove(&handle);
+                    self.apply_wanted();
+                }
+            }
+        }
+    }
+
+    /// Tell libcurl about activity on `socket` (or a timeout)
+    fn socket_action(&mut self, socket: curl_sys::curl_socket_t, mask: c_int) {
+        let mut running: c_int = 0;
+        let code =
+            unsafe { curl_sys::curl_multi_socket_action(self.multi, socket, mask, &mut running) };
+        if let Err(e) = check_multi_code(code) {
+            self.fail_all(e);
+        }
+    }
+
+    /// Act on the sockets and timeout libcurl asked for
+    fn apply_wanted(&mut self) {
+        let mut failed = Vec::new();
+        for (socket, what, easy) in mem::take(&mut self.wanted.sockets) {
+            if what == curl_sys::CURL_POLL_REMOVE {
+                self.sockets.remove(&socket);
+                continue;
+            }
+            if let Some(entry) = self.sockets.get_mut(&socket) {
+                entry.what = what;
+            } else {
+                let interest = Interest::READABLE.add(Interest::WRITABLE);
+                match AsyncFd::with_interest(SocketFd(socket as RawFd), interest) {
+                    Ok(fd) => {
+                        let waker = Waker::from(Arc::new(SocketWaker {
+                            socket,
+                            woken: self.woken.clone(),
+                        }));
+                        self.sockets.insert(socket, Socket { fd, what, waker });
+                    }
+                    Err(e) => {
+                        failed.push((easy as usize, e));
+                        continue;
+                    }
+                }
+            }
+            // Poll it once so its waker is registered
+            self.queue(socket);
+        }
+
+        match self.wanted.timeout.take() {
+            Some(ms) if ms < 0 => self.timer_armed = false,
+            Some(ms) => {
+                let deadline = Instant::now() + Duration::from_millis(ms as u64);
+                self.timer.as_mut().reset(deadline);
+                self.timer_armed = true;
+            }
+            None => {}
+        }
+
+        for (handle, e) in failed {
+            self.finish(handle, Err(CurlError::IoError(e)));
+        }
+    }
+
+    fn queue(&self, socket: curl_sys::curl_socket_t) {
+        self.woken
+            .sockets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(socket);
+    }
+
+    /// Run libcurl on a socket that became ready
+    ///
+    /// Returns `Some(true)` if the socket is still ready afterwards, and
+    /// `None` if it wasn't ready at all.
+    fn drive_socket(&mut self, socket: curl_sys::curl_socket_t) -> Option<bool> {
+        let entry = self.sockets.get(&socket)?;
+        let mut cx = Context::from_waker(&entry.waker);
+
+        let mut mask = 0;
+        let mut read_guard = None;
+        let mut write_guard = None;
+        if entry.what & curl_sys::CURL_POLL_IN != 0 {
+            match entry.fd.poll_read_ready(&mut cx) {
+                Poll::Ready(Ok(guard)) => {
+                    mask |= curl_sys::CURL_CSELECT_IN;
+                    read_guard = Some(guard);
+                }
+                Poll::Ready(Err(_)) => mask |= curl_sys::CURL_CSELECT_ERR,
+                Poll::Pending => {}
+            }
+        }
+        if entry.what & curl_sys::CURL_POLL_OUT != 0 {
+            match entry.fd.poll_write_ready(&mut cx) {
+                Poll::Ready(Ok(guard)) => {
+                    mask |= curl_sys::CURL_CSELECT_OUT;
+                    write_guard = Some(guard);
+                }
+                Poll::Ready(Err(_)) => mask |= curl_sys::CURL_CSELECT_ERR,
+                Poll::Pending => {}
+            }
+        }
+        if mask == 0 {
+            return None;
+        }
+
+        let mut running: c_int = 0;
+        let code =
+            unsafe { curl_sys::curl_multi_socket_action(self.multi, socket, mask, &mut running) };
+
+        // tokio only reports edges, while libcurl may leave data unread:
+        // readiness is only cleared once the socket really has none left.
+        // A socket libcurl dropped may already be closed, so it isn't touched.
+        let removed = self
+            .wanted
+            .sockets
+            .iter()
+            .any(|&(s, what, _)| s == socket && what == curl_sys::CURL_POLL_REMOVE);
+        let mut still_ready = false;
+        if !removed {
+            if let Some(mut guard) = read_guard {
+                still_ready |= guard
+                    .try_io(|fd| poll_now(fd.as_raw_fd(), libc::POLLIN))
+                    .is_ok();
+            }
+            if let Some(mut guard) = write_guard {
+                still_ready |= guard
+                    .try_io(|fd| poll_now(fd.as_raw_fd(), libc::POLLOUT))
+                    .is_ok();
+            }
+        }
+
+        if !removed && !still_ready {
+            // The waker is only registered by a poll that finds the socket
+            // not ready, so poll it again
+            self.queue(socket);
+        }
+
+        if let Err(e) = check_multi_code(code) {
+            self.fail_all(e);
+        }
+        self.apply_wanted();
+        Some(still_ready)
+    }
+
+    /// Hand out the results of finished transfers
+    fn read_messages(&mut self) {
+        loop {
+            let mut queued: c_int = 0;
+            let msg = unsafe { curl_sys::curl_multi_info_read(self.multi, &mut queued) };
+            if msg.is_null() {
+                break;
+            }
+            let msg = unsafe { &*msg };
+            if msg.msg != curl_sys::CURLMSG_DONE {
+                continue;
+            }
+            let handle = msg.easy_handle;
+            // `data` is a union whose `result` member holds the CURLcode
+            let code = msg.data as usize as curl_sys::CURLcode;
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.multi, handle);
+            }
+
+            let Some(running) = self.running.get_mut(&(handle as usize)) else {
+                continue;
+            };
+            match running.transfer.complete(code) {
+                Ok(true) => {
+                    let added = unsafe { curl_sys::curl_multi_add_handle(self.multi, handle) };
+                    if let Err(e) = check_multi_code(added) {
+                        self.finish(handle as usize, Err(e));
+                    }
+                    self.apply_wanted();
+                }
+                Ok(false) => self.finish(handle as usize, Ok(())),
+                Err(e) => self.finish(handle as usize, Err(e)),
+            }
+        }
+    }
+
+    /// Send the outcome of a transfer that is no longer in the multi handle
+    fn finish(&mut self, handle: usize, outcome: Result<()>) {
+        let Some(mut running) = self.running.remove(&handle) else {
+            return;
+        };
+        self.handles.remove(&running.id);
+        let result = outcome.and_then(|()| running.transfer.response());
+        let _ = running.done.send(result);
+    }
+
+    /// The multi handle itself failed: no transfer can go on
+    fn fail_all(&mut self, error: CurlError) {
+        let message = error.to_string();
+        for (handle, running) in mem::take(&mut self.running) {
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.multi, handle as *mut curl_sys::CURL);
+            }
+            let _ = running.done.send(Err(CurlError::Other(message.clone())));
+        }
+        self.handles.clear();
+    }
+}
+
+/// Whether `fd` is ready for `events` right now
+fn poll_now(fd: RawFd, events: c_short) -> io::Result<()> {
+    let mut pollfd = libc::pollfd {
+        fd,
+        events,
+        revents: 0,
+    };
+    let ready = unsafe { libc::poll(&mut pollfd, 1, 0) };
+    if ready > 0 && pollfd.revents != 0 {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::WouldBlock.into())
+    }
+}
+
+impl Future for Driver {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        *this
+            .woken
+            .driver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(cx.waker().clone());
+
+        let mut again = Vec::new();
+        loop {
+            let mut progress = false;
+
+            while !this.closed {
+                match this.commands.poll_recv(cx) {
+                    Poll::Ready(Some(command)) => {
+                        this.command(command);
+                        progress = true;
+                    }
+                    Poll::Ready(None) => this.closed = true,
+                    Poll::Pending => break,
+                }
+            }
+
+            if this.timer_armed && this.timer.as_mut().poll(cx).is_ready() {
+                this.timer_armed = false;
+                this.socket_action(curl_sys::CURL_SOCKET_TIMEOUT, 0);
+                this.apply_wanted();
+                progress = true;
+            }
+
+            let woken = mem::take(
+                &mut *this
+                    .woken
+                    .sockets
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            );
+            for socket in woken {
+                match this.drive_socket(socket) {
+                    Some(true) => {
+                        again.push(socket);
+                        progress = true;
+                    }
+                    Some(false) => progress = true,
+                    None => {}
+                }
+            }
+
+            this.read_messages();
+            if !progress {
+                break;
+            }
+        }
+
+        // Sockets with data left are driven again after yielding, so one
+        // busy connection can't hold up the runtime thread
+        if !again.is_empty() {
+            this.woken
+                .sockets
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend(again);
+            cx.waker().wake_by_ref();
+        }
+
+        if this.closed && this.running.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Driver {
+    fn drop(&mut self) {
+        // Deregister from tokio before libcurl closes the sockets
+        self.sockets.clear();
+        for handle in self.running.keys() {
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.multi, *handle as *mut curl_sys::CURL);
+            }
+        }
+        self.running.clear();
+        unsafe {
+            curl_sys::curl_multi_cleanup(self.multi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc as std_mpsc;
+    use std::thread;
+
+    /// Local server answering every request with its path, except `/stall`,
+    /// which never answers and reports once the client hangs up
+    fn serve() -> (String, std_mpsc::Receiver<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (hung_up, hang_ups) = std_mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let hung_up = hung_up.clone();
+                thread::spawn(move || {
+                    let mut head = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => head.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&head);
+                    let path = head.split(' ').nth(1).unwrap_or("/").to_string();
+                    if path == "/stall" {
+                        while matches!(stream.read(&mut buf), Ok(n) if n > 0) {}
+                        let _ = hung_up.send(());
+                        return;
+                    }
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        path.len(),
+                        path
+                    );
+                });
+            }
+        });
+        (base, hang_ups)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_complete() {
+        let (base, _) = serve();
+        let client = Client::new().unwrap();
+
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let client = client.clone();
+                let url = format!("{}/item/{}", base, i);
+                tokio::spawn(async move { client.get(url).await })
+            })
+            .collect();
+        for (i, task) in tasks.into_iter().enumerate() {
+            let response = task.await.unwrap().unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().unwrap(), format!("/item/{}", i));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropped_send_aborts_the_transfer() {
+        let (base, hang_ups) = serve();
+        let client = Client::new().unwrap();
+
+        let pending = client.get(format!("{}/stall", base));
+        assert!(tokio::time::timeout(Duration::from_millis(200), pending)
+            .await
+            .is_err());
+
+        // Removing the easy handle closes its connection
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while hang_ups.try_recv().is_err() {
+            assert!(
+                Instant::now() < deadline,
+                "the stalled transfer wasn't aborted"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let response = client.get(format!("{}/after", base)).await.unwrap();
+        assert_eq!(response.text().unwrap(), "/after");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_expires_while_the_server_stalls() {
+        let (base, _) = serve();
+        let client = Client::new().unwrap();
+
+        let request = Request::get(format!("{}/stall", base)).timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let error = client.send(request).await.unwrap_err();
+        assert_eq!(error.code(), Some(curl_sys::CURLE_OPERATION_TIMEDOUT));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,542 @@
+//! Cookie jar kept in memory and synced with libcurl's cookie engine
+//!
+//! Cookies travel to and from libcurl as Netscape cookie file lines through
+//! `CURLOPT_COOKIELIST` and `CURLINFO_COOKIELIST`.
+
+use crate::error::{CurlError, Result};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Prefix libcurl puts before the domain of `HttpOnly` cookies
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// First line of files written by [`CookieJar::save_netscape`]
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File\n";
+
+/// `SameSite` attribute of a cookie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+#[cfg(feature = "json")]
+impl SameSite {
+    fn as_str(self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "strict" => Some(SameSite::Strict),
+            "lax" => Some(SameSite::Lax),
+            "none" => Some(SameSite::None),
+            _ => None,
+        }
+    }
+}
+
+/// A single cookie
+///
+/// # Examples
+/// ```
+/// use hyprcurl::Cookie;
+///
+/// let mut cookie = Cookie::new("session", "abc123", "example.com");
+/// cookie.secure = true;
+/// cookie.http_only = true;
+/// cookie.expires = Some(1_900_000_000);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Host the cookie belongs to, without a leading dot
+    pub domain: String,
+    /// Whether subdomains of `domain` get the cookie too
+    pub include_subdomains: bool,
+    pub path: String,
+    /// Only sent over HTTPS
+    pub secure: bool,
+    /// Hidden from scripts in a browser
+    pub http_only: bool,
+    /// Expiry as a Unix timestamp in seconds; `None` for a session cookie
+    pub expires: Option<u64>,
+    /// `SameSite` attribute
+    ///
+    /// libcurl doesn't keep this attribute, so it is only filled in from
+    /// JSON or by hand, and only written out as JSON.
+    pub same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// Session cookie for `domain` only, with path `/`
+    pub fn new(
+        name: impl Into<String>,
+        value: impl Into<String>,
+        domain: impl Into<String>,
+    ) -> Self {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            domain: domain.into(),
+            include_subdomains: false,
+            path: "/".to_string(),
+            secure: false,
+            http_only: false,
+            expires: None,
+            same_site: None,
+        }
+    }
+
+    /// Whether the cookie is stored under the same name, domain and path
+    fn same_key(&self, other: &Cookie) -> bool {
+        self.name == other.name
+            && self.path == other.path
+            && self.domain.eq_ignore_ascii_case(&other.domain)
+    }
+
+    /// Whether the parts libcurl keeps are the same
+    fn same_in_curl(&self, other: &Cookie) -> bool {
+        Cookie {
+            same_site: other.same_site,
+            ..self.clone()
+        } == *other
+    }
+
+    /// Refuse values that would spill into other fields of a Netscape line
+    fn check(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(CurlError::InvalidCookie("empty cookie name".to_string()));
+        }
+        if self.domain.is_empty() {
+            return Err(CurlError::InvalidCookie(format!(
+                "cookie {:?} has no domain",
+                self.name
+            )));
+        }
+        let fields = [&self.name, &self.value, &self.domain, &self.path];
+        if fields
+            .iter()
+            .any(|field| field.contains(['\t', '\r', '\n', '\0']))
+        {
+            return Err(CurlError::InvalidCookie(format!(
+                "cookie {:?} contains a tab, line break or NUL",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Line of a Netscape cookie file, as read and written by libcurl
+    pub(crate) fn to_netscape_line(&self) -> String {
+        format!(
+            "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.http_only { HTTP_ONLY_PREFIX } else { "" },
+            if self.include_subdomains { "." } else { "" },
+            self.domain,
+            netscape_bool(self.include_subdomains),
+            self.path,
+            netscape_bool(self.secure),
+            self.expires.unwrap_or(0),
+            self.name,
+            self.value
+        )
+    }
+
+    /// Parse a Netscape cookie file line; `None` for comments and blank lines
+    pub(crate) fn from_netscape_line(line: &str) -> Result<Option<Self>> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (http_only, line) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(rest) => (true, rest),
+            None if line.starts_with('#') || line.trim().is_empty() => return Ok(None),
+            None => (false, line),
+        };
+
+        let invalid = || CurlError::InvalidCookie(format!("malformed cookie line {:?}", line));
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 && fields.len() != 7 {
+            return Err(invalid());
+        }
+        let expires: u64 = fields[4].parse().map_err(|_| invalid())?;
+
+        let cookie = Cookie {
+            name: fields[5].to_string(),
+            // Cookies without a value may leave out the last field
+            value: fields.get(6).unwrap_or(&"").to_string(),
+            domain: fields[0].trim_start_matches('.').to_string(),
+            include_subdomains: fields[1].eq_ignore_ascii_case("TRUE"),
+            path: fields[2].to_string(),
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            http_only,
+            expires: (expires != 0).then_some(expires),
+            same_site: None,
+        };
+        cookie.check()?;
+        Ok(Some(cookie))
+    }
+
+    #[cfg(feature = "json")]
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "value": self.value,
+            "domain": self.domain,
+            "include_subdomains": self.include_subdomains,
+            "path": self.path,
+            "secure": self.secure,
+            "http_only": self.http_only,
+            "expires": self.expires,
+            "same_site": self.same_site.map(SameSite::as_str),
+        })
+    }
+
+    #[cfg(feature = "json")]
+    fn from_json_value(value: &serde_json::Value) -> Result<Self> {
+        let invalid = |what: &str| CurlError::InvalidCookie(format!("{} in {}", what, value));
+        let text = |key: &str| value.get(key).and_then(serde_json::Value::as_str);
+        let flag = |key: &str| {
+            value
+                .get(key)
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false)
+        };
+
+        let same_site = match text("same_site") {
+            Some(same_site) => {
+                Some(SameSite::parse(same_site).ok_or_else(|| invalid("unknown same_site"))?)
+            }
+            None => None,
+        };
+        let domain = text("domain").ok_or_else(|| invalid("missing domain"))?;
+        let cookie = Cookie {
+            name: text("name")
+                .ok_or_else(|| invalid("missing name"))?
+                .to_string(),
+            value: text("value").unwrap_or_default().to_string(),
+            domain: domain.trim_start_matches('.').to_string(),
+            // A leading dot means the same as in a Netscape file
+            include_subdomains: flag("include_subdomains") || domain.starts_with('.'),
+            path: text("path").unwrap_or("/").to_string(),
+            secure: flag("secure"),
+            http_only: flag("http_only"),
+            expires: value
+                .get("expires")
+                .and_then(serde_json::Value::as_u64)
+                .filter(|&expires| expires != 0),
+            same_site,
+        };
+        cookie.check()?;
+        Ok(cookie)
+    }
+}
+
+fn netscape_bool(value: bool) -> &'static str {
+    if value {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+/// Cookies shared between requests, handles and threads
+///
+/// A jar attached to a handle with [`Curl::set_cookie_jar`] is loaded into
+/// libcurl's cookie engine before every transfer, and the cookies set or
+/// removed by the responses are written back afterwards. Clones share the
+/// same cookies, so one jar can serve any number of handles and sessions.
+///
+/// [`Curl::set_cookie_jar`]: crate::Curl::set_cookie_jar
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Cookie, CookieJar, Session};
+///
+/// let jar = CookieJar::new();
+/// jar.load_netscape("cookies.txt").unwrap();
+/// jar.insert(Cookie::new("lang", "en", "example.com")).unwrap();
+///
+/// let session = Session::new().unwrap().cookie_jar(jar.clone());
+/// session.get("https://example.com/login").unwrap();
+///
+/// for cookie in jar.cookies() {
+///     println!("{}={} for {}", cookie.name, cookie.value, cookie.domain);
+/// }
+/// jar.save_netscape("cookies.txt").unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Arc<Mutex<Vec<Cookie>>>,
+}
+
+impl CookieJar {
+    /// Create an empty jar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Cookie>> {
+        // Every update leaves the list consistent, so a panic elsewhere
+        // doesn't poison the cookies
+        self.cookies.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add a cookie, replacing one with the same name, domain and path
+    ///
+    /// Fails with `CurlError::InvalidCookie` when the name or domain is
+    /// empty or a field contains a tab, line break or NUL.
+    pub fn insert(&self, cookie: Cookie) -> Result<()> {
+        cookie.check()?;
+        upsert(&mut self.lock(), cookie);
+        Ok(())
+    }
+
+    /// First cookie with this name, on any domain
+    pub fn get(&self, name: &str) -> Option<Cookie> {
+        self.lock().iter().find(|c| c.name == name).cloned()
+    }
+
+    /// Remove every cookie with this name on this domain
+    ///
+    /// Returns whether any cookie was removed.
+    pub fn remove(&self, name: &str, domain: &str) -> bool {
+        let mut cookies = self.lock();
+        let before = cookies.len();
+        cookies.retain(|c| !(c.name == name && c.domain.eq_ignore_ascii_case(domain)));
+        cookies.len() != before
+    }
+
+    /// Copy of all cookies, in the order they were added
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.lock().clone()
+    }
+
+    /// Number of cookies
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the jar holds no cookies
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all cookies
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Add the cookies of a Netscape cookie file's contents
+    ///
+    /// This is the format of curl's `-c`/`-b` files and most browser
+    /// extensions. Comments and blank lines are skipped.
+    pub fn add_netscape(&self, text: &str) -> Result<()> {
+        let mut parsed = Vec::new();
+        for line in text.lines() {
+            parsed.extend(Cookie::from_netscape_line(line)?);
+        }
+        let mut cookies = self.lock();
+        for cookie in parsed {
+            upsert(&mut cookies, cookie);
+        }
+        Ok(())
+    }
+
+    /// All cookies as the contents of a Netscape cookie file
+    pub fn to_netscape(&self) -> String {
+        let mut text = NETSCAPE_HEADER.to_string();
+        for cookie in self.lock().iter() {
+            text.push_str(&cookie.to_netscape_line());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Add the cookies of a Netscape cookie file
+    pub fn load_netscape(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.add_netscape(&fs::read_to_string(path)?)
+    }
+
+    /// Write all cookies to a Netscape cookie file
+    pub fn save_netscape(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(fs::write(path, self.to_netscape())?)
+    }
+
+    /// Add the cookies of a JSON array written by [`to_json`](Self::to_json)
+    ///
+    /// Only `name` and `domain` are required; `path` defaults to `/`.
+    #[cfg(feature = "json")]
+    pub fn add_json(&self, text: &str) -> Result<()> {
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| CurlError::InvalidCookie(format!("invalid JSON: {}", e)))?;
+        let items = value
+            .as_array()
+            .ok_or_else(|| CurlError::InvalidCookie("expected a JSON array".to_string()))?;
+        let parsed = items
+            .iter()
+            .map(Cookie::from_json_value)
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut cookies = self.lock();
+        for cookie in parsed {
+            upsert(&mut cookies, cookie);
+        }
+        Ok(())
+    }
+
+    /// All cookies as a JSON array of objects
+    ///
+    /// Unlike the Netscape format, this keeps [`Cookie::same_site`].
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let items: Vec<_> = self.lock().iter().map(Cookie::to_json_value).collect();
+        serde_json::Value::Array(items).to_string()
+    }
+
+    /// Add the cookies of a JSON file written by [`save_json`](Self::save_json)
+    #[cfg(feature = "json")]
+    pub fn load_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.add_json(&fs::read_to_string(path)?)
+    }
+
+    /// Write all cookies to a JSON file
+    #[cfg(feature = "json")]
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(fs::write(path, self.to_json())?)
+    }
+
+    /// Write back what a transfer did to the cookies it was loaded with
+    ///
+    /// Only cookies the transfer added, changed or removed are touched, so
+    /// changes made meanwhile through other handles are kept.
+    pub(crate) fn merge(&self, loaded: &[Cookie], current: Vec<Cookie>) {
+        let mut cookies = self.lock();
+        for old in loaded {
+            if !current.iter().any(|c| c.same_key(old)) {
+                cookies.retain(|c| !c.same_key(old));
+            }
+        }
+        for cookie in current {
+            if !loaded.iter().any(|old| old.same_in_curl(&cookie)) {
+                upsert(&mut cookies, cookie);
+            }
+        }
+    }
+}
+
+/// Replace the cookie with the same key or append a new one
+///
+/// A cookie coming back from libcurl has no `SameSite`; the one already in
+/// the jar keeps it.
+fn upsert(cookies: &mut Vec<Cookie>, mut cookie: Cookie) {
+    match cookies.iter_mut().find(|c| c.same_key(&cookie)) {
+        Some(existing) => {
+            if cookie.same_site.is_none() {
+                cookie.same_site = existing.same_site;
+            }
+            *existing = cookie;
+        }
+        None => cookies.push(cookie),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Curl;
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let text = "# Netscape HTTP Cookie File\n\
+                    \n\
+                    #HttpOnly_.example.com\tTRUE\t/\tTRUE\t1900000000\tsid\tabc\n\
+                    api.example.com\tFALSE\t/v1\tFALSE\t0\tempty\n";
+        let jar = CookieJar::new();
+        jar.add_netscape(text).unwrap();
+
+        let sid = jar.get("sid").unwrap();
+        assert_eq!(sid.domain, "example.com");
+        assert!(sid.include_subdomains && sid.secure && sid.http_only);
+        assert_eq!(sid.expires, Some(1_900_000_000));
+        let empty = jar.get("empty").unwrap();
+        assert_eq!((empty.value.as_str(), empty.expires), ("", None));
+
+        let again = CookieJar::new();
+        again.add_netscape(&jar.to_netscape()).unwrap();
+        assert_eq!(again.cookies(), jar.cookies());
+
+        assert!(jar.add_netscape("example.com\tFALSE\t/").is_err());
+        assert!(jar.insert(Cookie::new("a", "x\ty", "example.com")).is_err());
+    }
+
+    #[test]
+    fn test_merge_keeps_unrelated_changes() {
+        let jar = CookieJar::new();
+        let kept = Cookie::new("kept", "1", "example.com");
+        let dropped = Cookie::new("dropped", "1", "example.com");
+        let mut strict = Cookie::new("strict", "1", "example.com");
+        strict.same_site = Some(SameSite::Strict);
+        for cookie in [&kept, &dropped, &strict] {
+            jar.insert(cookie.clone()).unwrap();
+        }
+        let loaded = jar.cookies();
+
+        // Another handle changes `kept` while the transfer runs
+        jar.insert(Cookie::new("kept", "2", "example.com")).unwrap();
+        let mut changed = strict.clone();
+        changed.value = "2".to_string();
+        changed.same_site = None;
+        let new = Cookie::new("new", "1", "example.com");
+        jar.merge(&loaded, vec![kept, changed, new]);
+
+        let names: Vec<_> = jar
+            .cookies()
+            .into_iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect();
+        assert_eq!(names, ["kept=2", "strict=2", "new=1"]);
+        assert_eq!(jar.get("strict").unwrap().same_site, Some(SameSite::Strict));
+    }
+
+    #[test]
+    fn test_jar_loads_into_handle() {
+        let jar = CookieJar::new();
+        let mut cookie = Cookie::new("sid", "abc", "example.com");
+        cookie.http_only = true;
+        cookie.expires = Some(4_000_000_000);
+        jar.insert(cookie.clone()).unwrap();
+
+        let mut curl = Curl::new().unwrap();
+        curl.set_cookie_jar(jar).unwrap();
+        curl.prepare_transfer().unwrap();
+        assert_eq!(curl.cookies().unwrap(), [cookie]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_round_trip() {
+        let jar = CookieJar::new();
+        jar.add_json(
+            r#"[{"name": "a", "value": "1", "domain": ".example.com", "same_site": "lax"},
+                {"name": "b", "domain": "example.org", "secure": true, "expires": 1900000000}]"#,
+        )
+        .unwrap();
+        let a = jar.get("a").unwrap();
+        assert_eq!((a.domain.as_str(), a.path.as_str()), ("example.com", "/"));
+        assert_eq!(a.same_site, Some(SameSite::Lax));
+        assert!(a.include_subdomains);
+        assert!(!jar.get("b").unwrap().include_subdomains);
+
+        let again = CookieJar::new();
+        again.add_json(&jar.to_json()).unwrap();
+        assert_eq!(again.cookies(), jar.cookies());
+
+        assert!(jar.add_json(r#"[{"name": "c"}]"#).is_err());
+    }
+}
@@ -1,21 +1,56 @@
 //! Core Curl wrapper implementation
 
+use crate::abort::{self, xferinfo_func, AbortHandle};
+use crate::body::{read_func, seek_func, BodyReader, BodySource};
+use crate::capabilities::Capabilities;
+use crate::cookie::{Cookie, CookieJar};
 use crate::error::{check_code, CurlError, Result};
-use crate::types::{Browser, CurlOpt, HttpVersion};
+use crate::ffi;
+use crate::header::{parse_header_line, HeaderMap};
+use crate::multipart::{Mime, Multipart};
+use crate::redirect::{Attempt, PostRedirect, RedirectPolicy};
+use crate::response::Response;
+use crate::share::{Share, ShareData};
+use crate::stream::{sink_write_func, ResponseStream, WriteSink};
+use crate::types::{Browser, CurlInfo, CurlOpt, HttpVersion, Method, TlsVersion};
 use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
+use std::io::{Read, Seek, Write};
+use std::os::raw::{c_char, c_int, c_long, c_void};
 use std::ptr;
+use std::time::Duration;
 
 /// Main Curl handle wrapper
 pub struct Curl {
     handle: *mut curl_sys::CURL,
-    headers: *mut curl_sys::curl_slist,
-    error_buffer: [c_char; curl_sys::CURL_ERROR_SIZE],
+    headers: *mut curl_sys::curl_slist, // Built from `header_map` before each transfer
+    header_map: HeaderMap,
+    // Boxed so the pointer handed to CURLOPT_ERRORBUFFER survives moves of `Curl`
+    error_buffer: Box<[c_char; curl_sys::CURL_ERROR_SIZE]>,
     // Store strings to keep them alive for libcurl
     stored_strings: Vec<CString>,
     stored_url: Option<CString>,
-    stored_postfields: Option<CString>,
-    stored_headers: Vec<CString>, // Keep header strings alive
+    stored_body: Option<Vec<u8>>, // Owned request body passed by pointer
+    body_reader: Option<Box<BodyReader>>, // Streamed request body
+    mime: Option<Mime>,           // multipart/form-data body
+    has_body: bool,
+    method: Option<Method>, // Last method set with `set_method`
+    redirect_policy: RedirectPolicy,
+    post_redirect: PostRedirect,
+    redirected: Option<Redirected>, // Set while following redirects by hand
+    final_url: Option<String>,      // Last hop of a hand-followed chain
+    cookie_jar: Option<CookieJar>,
+    loaded_cookies: Vec<Cookie>, // Jar contents handed to libcurl for this transfer
+    share: Option<Share>,        // Kept alive for as long as libcurl uses it
+    abort: Option<Box<AbortHandle>>, // Boxed for CURLOPT_XFERINFODATA
+    impersonation_gaps: Vec<String>, // Left out by the last browser impersonation
+}
+
+/// Request state from before a hand-followed redirect chain changed it
+struct Redirected {
+    count: usize,
+    url: String,
+    headers: HeaderMap,
+    method: Option<Method>,
 }
 
 impl Curl {
@@ -32,41 +67,56 @@ impl Curl {
         let mut curl = Curl {
             handle,
             headers: ptr::null_mut(),
-            error_buffer: [0; curl_sys::CURL_ERROR_SIZE],
+            header_map: HeaderMap::new(),
+            error_buffer: Box::new([0; curl_sys::CURL_ERROR_SIZE]),
             stored_strings: Vec::new(),
             stored_url: None,
-            stored_postfields: None,
-            stored_headers: Vec::new(),
+            stored_body: None,
+            body_reader: None,
+            mime: None,
+            has_body: false,
+            method: None,
+            redirect_policy: RedirectPolicy::None,
+            post_redirect: PostRedirect::default(),
+            redirected: None,
+            final_url: None,
+            cookie_jar: None,
+            loaded_cookies: Vec::new(),
+            share: None,
+            abort: None,
+            impersonation_gaps: Vec::new(),
         };
+        curl.apply_base_options()?;
+
+        Ok(curl)
+    }
 
+    /// Options every handle starts with, set by [`new`](Self::new) and
+    /// again by [`reset`](Self::reset)
+    fn apply_base_options(&mut self) -> Result<()> {
         // Set error buffer
         unsafe {
             curl_sys::curl_easy_setopt(
-                curl.handle,
+                self.handle,
                 curl_sys::CURLOPT_ERRORBUFFER,
-                curl.error_buffer.as_ptr(),
+                self.error_buffer.as_ptr(),
             );
         }
 
         // Disable signals (important for multi-threading)
-        curl.setopt_long(CurlOpt::NoSignal, 1)?;
+        self.setopt_long(CurlOpt::NoSignal, 1)?;
 
-        // Set follow redirects
-        curl.setopt_long(CurlOpt::FollowLocation, 1)?;
-
-        // Set user agent to avoid default issues
-        curl.setopt_str(CurlOpt::UserAgent, "curl-cffi-rs/0.1.0")?;
-
-        // Set secure SSL defaults (like curl-cffi Python)
-        curl.set_ssl_verify(None)?; // Enable SSL verification with default CA
+        // Keep proxy CONNECT responses out of the response headers
+        self.setopt_long(CurlOpt::SuppressConnectHeaders, 1)?;
 
         // Set follow redirects
-        curl.setopt_long(CurlOpt::FollowLocation, 1)?;
+        self.set_redirect_policy(RedirectPolicy::default())?;
 
         // Set user agent to avoid default issues
-        curl.setopt_str(CurlOpt::UserAgent, "curl-cffi-rs/0.1.0")?;
+        self.setopt_str(CurlOpt::UserAgent, "curl-cffi-rs/0.1.0")?;
 
-        Ok(curl)
+        // Set secure SSL defaults (like curl-cffi Python)
+        self.set_ssl_verify(None) // Enable SSL verification with default CA
     }
 
     /// Set URL to fetch
@@ -93,6 +143,11 @@ impl Curl {
 
     /// Set a string option
     pub fn setopt_str(&mut self, option: CurlOpt, value: &str) -> Result<()> {
+        // POSTFIELDS is binary-safe, so it goes through the sized body path
+        if option == CurlOpt::PostFields {
+            return self.set_post_bytes(value.as_bytes());
+        }
+
         let c_value = CString::new(value)
             .map_err(|_| CurlError::InvalidOption(format!("Invalid string: {}", value)))?;
 
@@ -102,73 +157,596 @@ impl Curl {
         }
 
         // Store the string to keep it alive (libcurl doesn't copy it)
-        // Special handling for POSTFIELDS which needs its own storage
-        if option == CurlOpt::PostFields {
-            self.stored_postfields = Some(c_value);
-        } else {
-            self.stored_strings.push(c_value);
-        }
+        self.stored_strings.push(c_value);
 
         Ok(())
     }
 
-    /// Add a header
+    /// Add a header from a `"Name: value"` line
+    ///
+    /// The header is appended to the request headers, see
+    /// [`headers_mut`](Self::headers_mut). A line with an empty value, like
+    /// `"Accept:"`, stops libcurl from sending that header itself.
     pub fn add_header(&mut self, header: &str) -> Result<()> {
-        let c_header = CString::new(header)
-            .map_err(|_| CurlError::InvalidOption(format!("Invalid header: {}", header)))?;
+        let (name, value) = parse_header_line(header)?;
+        self.header_map.append(name, value)
+    }
+
+    /// Replace all request headers
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, HeaderMap};
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("Accept", "application/json").unwrap();
+    /// headers.insert("Authorization", "Bearer token").unwrap();
+    ///
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_headers(headers);
+    /// ```
+    pub fn set_headers(&mut self, headers: HeaderMap) {
+        self.header_map = headers;
+    }
+
+    /// Request headers sent with the next transfer
+    pub fn headers(&self) -> &HeaderMap {
+        &self.header_map
+    }
+
+    /// Mutable access to the request headers sent with the next transfer
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.header_map
+    }
+
+    /// Set HTTP version
+    pub fn set_http_version(&mut self, version: HttpVersion) -> Result<()> {
+        self.setopt_long(CurlOpt::HttpVersion, version.to_curl())
+    }
+
+    /// Limit the TLS versions offered, from `min` up to `max`, or up to the
+    /// highest the TLS library supports with `None`
+    pub fn set_tls_version(&mut self, min: TlsVersion, max: Option<TlsVersion>) -> Result<()> {
+        let max = max.map_or(ffi::CURL_SSLVERSION_MAX_DEFAULT, TlsVersion::to_curl_max);
+        self.setopt_long(CurlOpt::SslVersion, min.to_curl() | max)
+    }
+
+    /// Abort the transfer if it takes longer than `timeout` in total
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::Curl;
+    /// # use std::time::Duration;
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_timeout(Duration::from_secs(60)).unwrap();
+    /// curl.set_connect_timeout(Duration::from_millis(2500)).unwrap();
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.setopt_long(CurlOpt::TimeoutMs, duration_to_ms(timeout))
+    }
+
+    /// Give up connecting (including the TLS handshake) after `timeout`
+    pub fn set_connect_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.setopt_long(CurlOpt::ConnectTimeoutMs, duration_to_ms(timeout))
+    }
 
+    /// Clear a string option back to libcurl's default
+    fn unset_str(&mut self, option: CurlOpt) -> Result<()> {
         unsafe {
-            self.headers = curl_sys::curl_slist_append(self.headers, c_header.as_ptr());
-            if self.headers.is_null() {
-                return Err(CurlError::Other("Failed to add header".to_string()));
+            let code =
+                curl_sys::curl_easy_setopt(self.handle, option.to_raw(), ptr::null::<c_char>());
+            check_code(code)?;
+        }
+        Ok(())
+    }
+
+    /// Set the request method
+    ///
+    /// GET, HEAD and POST use libcurl's native modes. Every other method is
+    /// sent with `CURLOPT_CUSTOMREQUEST`, so a body set with
+    /// [`set_post_data`](Self::set_post_data) is still transmitted, e.g. for
+    /// PUT or PATCH. A GET with a body is sent the same way.
+    ///
+    /// Call this after setting the body. HEAD with a body fails with
+    /// [`CurlError::InvalidOption`], since it would go unsent.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, Method};
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/put").unwrap();
+    /// curl.set_post_data(r#"{"key": "value"}"#).unwrap();
+    /// curl.set_method(&Method::Put).unwrap();
+    /// ```
+    pub fn set_method(&mut self, method: &Method) -> Result<()> {
+        if *method == Method::Head && self.has_body {
+            return Err(CurlError::InvalidOption(
+                "a HEAD request can't send a body".to_string(),
+            ));
+        }
+        self.unset_str(CurlOpt::CustomRequest)?;
+        self.setopt_long(CurlOpt::NoBody, 0)?;
+        self.method = Some(method.clone());
+
+        match method {
+            Method::Get if !self.has_body => self.setopt_long(CurlOpt::HttpGet, 1),
+            Method::Head => self.setopt_long(CurlOpt::NoBody, 1),
+            Method::Post => {
+                // Without POSTFIELDS libcurl would read the body from stdin
+                if !self.has_body {
+                    self.set_post_data("")?;
+                }
+                self.restore_body()
+            }
+            other => {
+                if self.has_body {
+                    self.restore_body()?;
+                }
+                self.setopt_str(CurlOpt::CustomRequest, other.as_str())
+            }
+        }
+    }
+
+    /// Put libcurl back in the mode that sends the body set on this handle,
+    /// which an earlier `CURLOPT_HTTPGET` may have switched off
+    fn restore_body(&mut self) -> Result<()> {
+        match &self.mime {
+            // CURLOPT_POST would switch a multipart body back to a plain POST;
+            // setting the same MIME body again restores a multipart POST
+            Some(mime) => unsafe {
+                check_code(curl_sys::curl_easy_setopt(
+                    self.handle,
+                    ffi::CURLOPT_MIMEPOST,
+                    mime.as_ptr(),
+                ))
+            },
+            None => self.setopt_long(CurlOpt::Post, 1),
+        }
+    }
+
+    /// Turn the request into a GET that leaves the body out, as a redirect
+    /// that changes the method does; the body stays set for the next transfer
+    fn set_bodiless_get(&mut self) -> Result<()> {
+        self.unset_str(CurlOpt::CustomRequest)?;
+        self.setopt_long(CurlOpt::NoBody, 0)?;
+        self.method = Some(Method::Get);
+        self.setopt_long(CurlOpt::HttpGet, 1)
+    }
+
+    /// Choose how redirects are followed
+    ///
+    /// The default is [`RedirectPolicy::Limited`] with 30 redirects.
+    /// [`set_browser_impersonation`](Self::set_browser_impersonation) sets its
+    /// own limit, so call this afterwards to override it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, RedirectPolicy};
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/redirect/1").unwrap();
+    /// curl.set_redirect_policy(RedirectPolicy::None).unwrap();
+    /// let response = curl.perform_response().unwrap();
+    /// assert_eq!(response.status(), 302);
+    /// ```
+    pub fn set_redirect_policy(&mut self, policy: RedirectPolicy) -> Result<()> {
+        match &policy {
+            RedirectPolicy::Limited(max) => {
+                self.setopt_long(CurlOpt::FollowLocation, 1)?;
+                self.setopt_long(CurlOpt::MaxRedirs, i64::from(*max))?;
             }
+            // Hand-followed policies see every 3xx response themselves
+            _ => self.setopt_long(CurlOpt::FollowLocation, 0)?,
         }
+        self.redirect_policy = policy;
+        Ok(())
+    }
 
-        // Store the header string to keep it alive
-        self.stored_headers.push(c_header);
+    /// Choose which redirects keep a POST a POST (`CURLOPT_POSTREDIR`)
+    pub fn set_post_redirect(&mut self, post_redirect: PostRedirect) -> Result<()> {
+        self.setopt_long(CurlOpt::PostRedir, post_redirect.to_curl())?;
+        self.post_redirect = post_redirect;
         Ok(())
     }
 
-    /// Set HTTP version
-    pub fn set_http_version(&mut self, version: HttpVersion) -> Result<()> {
-        self.setopt_long(CurlOpt::HttpVersion, version.to_curl())
+    /// Keep this handle's cookies in `jar`
+    ///
+    /// The jar's cookies replace those in libcurl's cookie engine before
+    /// every transfer, and the cookies set by responses are stored back into
+    /// the jar when it finishes. Handles sharing a jar see each other's
+    /// cookies from their next transfer on.
+    ///
+    /// Fails when the handle uses a [`Share`] that shares cookies: loading
+    /// the jar would replace the cookies of every handle on the share.
+    pub fn set_cookie_jar(&mut self, jar: CookieJar) -> Result<()> {
+        self.check_cookie_sources(self.share.as_ref(), Some(&jar))?;
+        // An empty file name turns on the in-memory cookie engine
+        self.setopt_str(CurlOpt::CookieFile, "")?;
+        self.cookie_jar = Some(jar);
+        Ok(())
+    }
+
+    /// Use the caches of `share` instead of the handle's own
+    ///
+    /// The handle keeps the share alive and stays attached to it across
+    /// [`reset`](Self::reset).
+    pub fn set_share(&mut self, share: &Share) -> Result<()> {
+        self.check_cookie_sources(Some(share), self.cookie_jar.as_ref())?;
+        unsafe {
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_SHARE,
+                share.raw_handle(),
+            );
+            check_code(code)?;
+        }
+        self.share = Some(share.clone());
+        // A handle trims the shared pool to its own limit, which is 5 by
+        // default
+        self.setopt_long(CurlOpt::MaxConnects, share.connection_limit() as i64)?;
+        if share.shares(ShareData::Cookies) {
+            // Shared cookies are only read and stored with the engine on
+            self.setopt_str(CurlOpt::CookieFile, "")?;
+        }
+        Ok(())
+    }
+
+    /// Handle that aborts this handle's transfers from another thread
+    ///
+    /// Created on first use; later calls return the same handle until
+    /// [`reset`](Self::reset).
+    pub fn abort_handle(&mut self) -> Result<AbortHandle> {
+        if let Some(abort) = &self.abort {
+            return Ok(AbortHandle::clone(abort));
+        }
+        let abort = AbortHandle::new();
+        self.set_abort_handle(abort.clone())?;
+        Ok(abort)
+    }
+
+    /// Let `abort` stop this handle's transfers, e.g. to abort several
+    /// handles at once
+    pub fn set_abort_handle(&mut self, abort: AbortHandle) -> Result<()> {
+        let abort = Box::new(abort);
+        unsafe {
+            check_code(curl_sys::curl_easy_setopt(
+                self.handle,
+                ffi::CURLOPT_XFERINFOFUNCTION,
+                xferinfo_func as *const c_void,
+            ))?;
+            check_code(curl_sys::curl_easy_setopt(
+                self.handle,
+                ffi::CURLOPT_XFERINFODATA,
+                &*abort as *const AbortHandle as *mut c_void,
+            ))?;
+            check_code(curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_NOPROGRESS,
+                0 as c_long,
+            ))?;
+        }
+        self.abort = Some(abort);
+        Ok(())
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.abort.as_ref().is_some_and(|abort| abort.is_aborted())
+    }
+
+    /// Like `is_aborted`, but also runs the thread's interrupt check, for
+    /// waits outside a transfer
+    pub(crate) fn should_stop(&self) -> bool {
+        self.abort.as_deref().is_some_and(abort::should_stop)
+    }
+
+    /// A cookie jar and a share holding cookies would both own the cookies
+    fn check_cookie_sources(&self, share: Option<&Share>, jar: Option<&CookieJar>) -> Result<()> {
+        match (share, jar) {
+            (Some(share), Some(_)) if share.shares(ShareData::Cookies) => {
+                Err(CurlError::InvalidOption(
+                    "a cookie jar can't be used with a share that shares cookies".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Cookies currently held by libcurl's cookie engine
+    pub fn cookies(&self) -> Result<Vec<Cookie>> {
+        let mut list: *mut curl_sys::curl_slist = ptr::null_mut();
+        unsafe {
+            let ret =
+                curl_sys::curl_easy_getinfo(self.handle, curl_sys::CURLINFO_COOKIELIST, &mut list);
+            check_code(ret)?;
+        }
+
+        let mut cookies = Vec::new();
+        let mut result = Ok(());
+        let mut item = list;
+        while !item.is_null() && result.is_ok() {
+            let line = unsafe { CStr::from_ptr((*item).data) }.to_string_lossy();
+            match Cookie::from_netscape_line(&line) {
+                Ok(cookie) => cookies.extend(cookie),
+                Err(e) => result = Err(e),
+            }
+            item = unsafe { (*item).next };
+        }
+        unsafe { curl_sys::curl_slist_free_all(list) };
+        result.map(|_| cookies)
+    }
+
+    /// Remove every cookie from libcurl's cookie engine
+    ///
+    /// Cookies survive [`reset`](Self::reset), so this is how a handle
+    /// forgets them before serving an unrelated request. A cookie jar set
+    /// on the handle is left alone.
+    pub fn clear_cookies(&mut self) -> Result<()> {
+        self.cookie_command("ALL")
+    }
+
+    /// Pass a cookie line or command (`ALL`, `SESS`, ...) to `CURLOPT_COOKIELIST`
+    fn cookie_command(&mut self, command: &str) -> Result<()> {
+        // libcurl parses the line right away, so it isn't kept alive
+        let c_command = CString::new(command)
+            .map_err(|_| CurlError::InvalidCookie("cookie contains a NUL byte".to_string()))?;
+        unsafe {
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_COOKIELIST,
+                c_command.as_ptr(),
+            );
+            check_code(code)
+        }
+    }
+
+    /// Replace the cookie engine's cookies with the jar's
+    fn load_cookie_jar(&mut self) -> Result<()> {
+        let Some(jar) = &self.cookie_jar else {
+            return Ok(());
+        };
+        let cookies = jar.cookies();
+        self.cookie_command("ALL")?;
+        for cookie in &cookies {
+            self.cookie_command(&cookie.to_netscape_line())?;
+        }
+        self.loaded_cookies = cookies;
+        Ok(())
+    }
+
+    /// Store what the last transfer did to the cookies back into the jar
+    fn store_cookie_jar(&mut self) -> Result<()> {
+        let Some(jar) = &self.cookie_jar else {
+            return Ok(());
+        };
+        let current = self.cookies()?;
+        jar.merge(&self.loaded_cookies, current.clone());
+        self.loaded_cookies = current;
+        Ok(())
     }
 
     /// Set POST data from a string
     pub fn set_post_data(&mut self, data: &str) -> Result<()> {
-        self.setopt_str(CurlOpt::PostFields, data)?;
-        // Enable POST
-        self.setopt_long(CurlOpt::CustomRequest, 0)?; // Reset method if it was set
-        Ok(())
+        self.set_post_bytes(data.as_bytes())
     }
 
     /// Set POST data from bytes
+    ///
+    /// The data may contain NUL bytes; libcurl copies it with
+    /// `CURLOPT_COPYPOSTFIELDS`, so the slice doesn't need to outlive the call.
     pub fn set_post_bytes(&mut self, data: &[u8]) -> Result<()> {
-        // Store the data
-        let c_data = CString::new(data)
-            .map_err(|_| CurlError::InvalidOption("POST data contains null byte".to_string()))?;
+        self.clear_body();
+        self.set_post_field_size(data.len())?;
+        unsafe {
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_COPYPOSTFIELDS,
+                data.as_ptr() as *const c_char,
+            );
+            check_code(code)?;
+        }
+
+        self.stored_body = None;
+        self.has_body = true;
+        Ok(())
+    }
 
+    /// Set the request body from an owned buffer
+    ///
+    /// Unlike [`set_post_bytes`](Self::set_post_bytes) the buffer is not
+    /// copied: the handle keeps it and hands libcurl a pointer to it.
+    /// Accepts anything convertible into `Vec<u8>`, including `bytes::Bytes`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::Curl;
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/post").unwrap();
+    /// curl.set_body(vec![0x08, 0x96, 0x01, 0x00]).unwrap();
+    /// ```
+    pub fn set_body(&mut self, data: impl Into<Vec<u8>>) -> Result<()> {
+        let data = data.into();
+        self.clear_body();
+        self.set_post_field_size(data.len())?;
         unsafe {
             let code = curl_sys::curl_easy_setopt(
                 self.handle,
                 curl_sys::CURLOPT_POSTFIELDS,
-                c_data.as_ptr(),
+                data.as_ptr() as *const c_char,
             );
             check_code(code)?;
+        }
+
+        // Moving the Vec doesn't move its heap buffer, so the pointer stays valid
+        self.stored_body = Some(data);
+        self.has_body = true;
+        Ok(())
+    }
 
-            // Set the size explicitly
-            let size_code = curl_sys::curl_easy_setopt(
+    /// Stream the request body from a reader
+    ///
+    /// The body is pulled through `CURLOPT_READFUNCTION` while the transfer
+    /// runs, so it never has to fit in memory. With a known `len` libcurl
+    /// sends a `Content-Length`; with `None` it uses chunked transfer
+    /// encoding (HTTP/1.1) or plain DATA frames (HTTP/2).
+    ///
+    /// The reader can't be rewound, so redirects that keep the body (307/308)
+    /// and auth retries fail; use [`set_body_seekable`](Self::set_body_seekable)
+    /// when that matters.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, Method};
+    /// let file = std::fs::File::open("logs.tar.gz").unwrap();
+    /// let len = file.metadata().unwrap().len();
+    ///
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/put").unwrap();
+    /// curl.set_body_reader(file, Some(len)).unwrap();
+    /// curl.set_method(&Method::Put).unwrap();
+    /// ```
+    pub fn set_body_reader<R>(&mut self, reader: R, len: Option<u64>) -> Result<()>
+    where
+        R: Read + Send + 'static,
+    {
+        self.set_body_source(BodySource::Plain(Box::new(reader)), len)
+    }
+
+    /// Stream the request body from a reader that libcurl can rewind
+    ///
+    /// Same as [`set_body_reader`](Self::set_body_reader), but the body is
+    /// also hooked up to `CURLOPT_SEEKFUNCTION` so it can be resent on
+    /// redirects and authentication retries.
+    pub fn set_body_seekable<R>(&mut self, reader: R, len: Option<u64>) -> Result<()>
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        self.set_body_source(BodySource::Seekable(Box::new(reader)), len)
+    }
+
+    pub(crate) fn set_body_source(&mut self, source: BodySource, len: Option<u64>) -> Result<()> {
+        self.clear_body();
+        let mut body = Box::new(BodyReader::new(source));
+        let userdata = &mut *body as *mut BodyReader as *mut c_void;
+
+        unsafe {
+            // A set POSTFIELDS would take precedence over the read callback
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_POSTFIELDS,
+                ptr::null::<c_char>(),
+            );
+            check_code(code)?;
+
+            let code = curl_sys::curl_easy_setopt(
                 self.handle,
-                curl_sys::CURLOPT_POSTFIELDSIZE,
-                data.len() as i64,
+                curl_sys::CURLOPT_READFUNCTION,
+                read_func as *const c_void,
             );
-            check_code(size_code)?;
+            check_code(code)?;
+            let code =
+                curl_sys::curl_easy_setopt(self.handle, curl_sys::CURLOPT_READDATA, userdata);
+            check_code(code)?;
+
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_SEEKFUNCTION,
+                seek_func as *const c_void,
+            );
+            check_code(code)?;
+            let code =
+                curl_sys::curl_easy_setopt(self.handle, curl_sys::CURLOPT_SEEKDATA, userdata);
+            check_code(code)?;
+
+            // -1 tells libcurl the size is unknown
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_POSTFIELDSIZE_LARGE,
+                len.map_or(-1, |len| len as curl_sys::curl_off_t),
+            );
+            check_code(code)?;
         }
+        self.setopt_long(CurlOpt::Post, 1)?;
 
-        self.stored_postfields = Some(c_data);
+        self.stored_body = None;
+        self.body_reader = Some(body);
+        self.has_body = true;
         Ok(())
     }
 
+    /// Send a multipart/form-data body
+    ///
+    /// Replaces any previously set body. The request is sent as a POST unless
+    /// [`set_method`](Self::set_method) picks another method afterwards.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, Multipart};
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/post").unwrap();
+    /// curl.set_multipart(Multipart::new().text("name", "value").file("upload", "report.pdf"))
+    ///     .unwrap();
+    /// ```
+    pub fn set_multipart(&mut self, form: Multipart) -> Result<()> {
+        let mime = Mime::new(self.handle, form)?;
+        self.clear_body();
+        unsafe {
+            // Don't leave POSTFIELDS pointing at the body freed below
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_POSTFIELDS,
+                ptr::null::<c_char>(),
+            );
+            check_code(code)?;
+            let code =
+                curl_sys::curl_easy_setopt(self.handle, ffi::CURLOPT_MIMEPOST, mime.as_ptr());
+            check_code(code)?;
+        }
+
+        self.stored_body = None;
+        self.mime = Some(mime);
+        self.has_body = true;
+        Ok(())
+    }
+
+    /// Drop any streamed or multipart body, leaving the callbacks pointed at nothing
+    fn clear_body(&mut self) {
+        if self.mime.is_some() {
+            // Detach before freeing: libcurl still holds a pointer to it
+            unsafe {
+                curl_sys::curl_easy_setopt(
+                    self.handle,
+                    ffi::CURLOPT_MIMEPOST,
+                    ptr::null_mut::<ffi::curl_mime>(),
+                );
+            }
+            self.mime = None;
+        }
+        if self.body_reader.take().is_some() {
+            unsafe {
+                curl_sys::curl_easy_setopt(
+                    self.handle,
+                    curl_sys::CURLOPT_READDATA,
+                    ptr::null_mut::<c_void>(),
+                );
+                curl_sys::curl_easy_setopt(
+                    self.handle,
+                    curl_sys::CURLOPT_SEEKDATA,
+                    ptr::null_mut::<c_void>(),
+                );
+            }
+        }
+    }
+
+    /// Set the exact body size so libcurl never falls back to `strlen`
+    fn set_post_field_size(&mut self, len: usize) -> Result<()> {
+        unsafe {
+            let code = curl_sys::curl_easy_setopt(
+                self.handle,
+                curl_sys::CURLOPT_POSTFIELDSIZE_LARGE,
+                len as curl_sys::curl_off_t,
+            );
+            check_code(code)
+        }
+    }
+
     /// Set HTTP/SOCKS proxy
     ///
     /// Supports: http://, https://, socks4://, socks4a://, socks5://, socks5h://
@@ -203,75 +781,218 @@ impl Curl {
         self.setopt_str(CurlOpt::ProxyUserPwd, &auth)
     }
 
-    /// Impersonate a browser
+    /// Impersonate a browser with libcurl-impersonate's
+    /// `curl_easy_impersonate`
+    ///
+    /// `target` is a libcurl-impersonate target such as `"chrome120"`. With
+    /// `default_headers`, the browser's usual headers are sent too; headers
+    /// added to this handle replace the ones with the same name.
     ///
-    /// Note: This requires libcurl-impersonate
-    pub fn impersonate(&mut self, target: &str, _default_headers: bool) -> Result<()> {
+    /// The function is looked up at runtime, so everything else works with
+    /// a plain libcurl, where this fails with
+    /// [`CurlError::ImpersonateUnavailable`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::{Curl, CurlError};
+    /// let mut curl = Curl::new().unwrap();
+    /// match curl.impersonate("chrome120", true) {
+    ///     Err(CurlError::ImpersonateUnavailable) => println!("needs libcurl-impersonate"),
+    ///     other => other.unwrap(),
+    /// }
+    /// ```
+    pub fn impersonate(&mut self, target: &str, default_headers: bool) -> Result<()> {
+        let impersonate =
+            crate::impersonate::curl_easy_impersonate().ok_or(CurlError::ImpersonateUnavailable)?;
         let c_target = CString::new(target)
             .map_err(|_| CurlError::InvalidOption(format!("Invalid target: {}", target)))?;
-
-        // This is a custom function from libcurl-impersonate
-        // We'll need to bind it separately or use dlsym
-        unsafe {
-            // For now, just set user agent as a placeholder
-            // In real implementation, would call curl_easy_impersonate
-            let code = curl_sys::curl_easy_setopt(
-                self.handle,
-                curl_sys::CURLOPT_USERAGENT,
-                c_target.as_ptr(),
-            );
-            check_code(code)?;
-        }
-        Ok(())
+        check_code(unsafe {
+            impersonate(self.handle, c_target.as_ptr(), c_int::from(default_headers))
+        })
     }
 
     /// Perform the request and return response data
     pub fn perform(&mut self, buffer: &mut Vec<u8>) -> Result<()> {
-        use std::os::raw::c_void;
-
         // Clear the buffer first
         buffer.clear();
+        self.perform_into(buffer)
+    }
 
-        // Use a simple approach with a captured buffer
-        unsafe {
-            // Set write function using a closure that captures the buffer
-            extern "C" fn write_func(
-                ptr: *mut c_char,
-                size: usize,
-                nmemb: usize,
-                userdata: *mut c_void,
-            ) -> usize {
-                let total_size = size * nmemb;
-                if total_size == 0 || ptr.is_null() {
-                    return 0;
-                }
-
-                unsafe {
-                    let buffer = &mut *(userdata as *mut Vec<u8>);
-                    let data = std::slice::from_raw_parts(ptr as *const u8, total_size);
-                    buffer.extend_from_slice(data);
-                }
-                total_size
-            }
+    /// Perform the request, writing the response body into any sink
+    ///
+    /// The body is handed to `sink` chunk by chunk as it arrives, so large
+    /// downloads never have to fit in memory. If the sink returns an error
+    /// the transfer is aborted and the error comes back as
+    /// [`CurlError::SinkError`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::Curl;
+    /// let mut file = std::fs::File::create("download.bin").unwrap();
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/bytes/1024").unwrap();
+    /// curl.perform_into(&mut file).unwrap();
+    /// ```
+    pub fn perform_into<W: Write>(&mut self, sink: &mut W) -> Result<()> {
+        let mut sink = WriteSink::new(sink);
+        if !self.redirect_policy.is_native() {
+            sink.hold_redirects(self.handle);
+        }
 
-            // Set write function
+        unsafe {
             let code = curl_sys::curl_easy_setopt(
                 self.handle,
                 curl_sys::CURLOPT_WRITEFUNCTION,
-                write_func as *const c_void,
+                sink_write_func as *const c_void,
             );
             check_code(code)?;
 
-            // Set write data
             let code = curl_sys::curl_easy_setopt(
                 self.handle,
                 curl_sys::CURLOPT_WRITEDATA,
-                buffer as *mut Vec<u8> as *mut c_void,
+                &mut sink as *mut WriteSink as *mut c_void,
             );
             check_code(code)?;
+        }
+
+        let result = self.perform_hops(&mut sink);
+        let restored = self.finish_redirects();
+        result.and(restored)
+    }
+
+    /// Run the transfer, plus one more for each redirect followed by hand
+    fn perform_hops(&mut self, sink: &mut WriteSink) -> Result<()> {
+        loop {
+            self.prepare_transfer()?;
+            let code = unsafe { curl_sys::curl_easy_perform(self.handle) };
+
+            if let Some(err) = sink.take_error() {
+                return Err(CurlError::SinkError(err));
+            }
+            self.transfer_result(code)?;
+
+            if !self.follow_redirect()? {
+                return sink.release_held().map_err(CurlError::SinkError);
+            }
+            sink.next_hop();
+        }
+    }
+
+    /// Point the handle at the next hop when the redirect policy follows the
+    /// last response by hand
+    ///
+    /// Returns `false` when the last response is the final one. Changes to
+    /// the request are undone by [`finish_redirects`](Self::finish_redirects).
+    pub(crate) fn follow_redirect(&mut self) -> Result<bool> {
+        if self.redirect_policy.is_native() {
+            return Ok(false);
+        }
+        let status = u16::try_from(self.response_code()?).unwrap_or_default();
+        if !matches!(status, 301 | 302 | 303 | 307 | 308) {
+            return Ok(false);
+        }
+        let Some(next_url) = self.getinfo_str(CurlInfo::RedirectUrl)? else {
+            return Ok(false);
+        };
 
-            // Apply headers if any
-            if !self.headers.is_null() {
+        let url = self.request_url().unwrap_or_default();
+        let same_origin = crate::url::same_origin(&url, &next_url)?;
+        let attempt = Attempt {
+            status,
+            url: &url,
+            next_url: &next_url,
+            previous: self.redirected.as_ref().map_or(0, |r| r.count),
+            same_origin,
+        };
+        if !self.redirect_policy.allows(&attempt)? {
+            return Ok(false);
+        }
+
+        let redirected = self.redirected.get_or_insert_with(|| Redirected {
+            count: 0,
+            url: url.clone(),
+            headers: self.header_map.clone(),
+            method: self.method.clone(),
+        });
+        redirected.count += 1;
+
+        // Same rules libcurl applies when it follows redirects itself
+        let method = self.method.clone().unwrap_or(if self.has_body {
+            Method::Post
+        } else {
+            Method::Get
+        });
+        let to_get = match status {
+            303 => !matches!(method, Method::Get | Method::Head) && !self.keeps_post(&method, 303),
+            301 | 302 => method == Method::Post && !self.keeps_post(&method, status),
+            _ => false,
+        };
+        if to_get {
+            self.set_bodiless_get()?;
+        } else if let Some(body) = &mut self.body_reader {
+            if !body.rewind()? {
+                return Err(CurlError::from_curl_code(curl_sys::CURLE_SEND_FAIL_REWIND));
+            }
+        }
+
+        if !same_origin {
+            self.header_map.remove("authorization");
+            self.header_map.remove("cookie");
+        }
+        self.set_url(&next_url)?;
+        Ok(true)
+    }
+
+    fn keeps_post(&self, method: &Method, status: u16) -> bool {
+        *method == Method::Post && self.post_redirect.keeps_post(status)
+    }
+
+    /// Restore the request a hand-followed redirect chain changed, keeping
+    /// the final URL for [`effective_url`](Self::effective_url)
+    pub(crate) fn finish_redirects(&mut self) -> Result<()> {
+        let Some(redirected) = self.redirected.take() else {
+            return Ok(());
+        };
+        self.final_url = Some(self.effective_url()?);
+        self.header_map = redirected.headers;
+        self.set_url(&redirected.url)?;
+        if self.method != redirected.method {
+            let method = redirected.method.clone().unwrap_or(if self.has_body {
+                Method::Post
+            } else {
+                Method::Get
+            });
+            self.set_method(&method)?;
+            self.method = redirected.method;
+        }
+        Ok(())
+    }
+
+    /// Hand libcurl the state that is only applied right before a transfer
+    pub(crate) fn prepare_transfer(&mut self) -> Result<()> {
+        if self.is_aborted() {
+            return Err(CurlError::Cancelled);
+        }
+        self.final_url = None;
+        self.free_header_list();
+        self.load_cookie_jar()?;
+
+        // libcurl copies each line into the list
+        let mut list: *mut curl_sys::curl_slist = ptr::null_mut();
+        for line in self.header_map.to_curl_lines() {
+            let c_line = CString::new(line)
+                .map_err(|_| CurlError::InvalidHeader("header contains a NUL byte".to_string()))?;
+            let appended = unsafe { curl_sys::curl_slist_append(list, c_line.as_ptr()) };
+            if appended.is_null() {
+                unsafe { curl_sys::curl_slist_free_all(list) };
+                return Err(CurlError::Other("Failed to add header".to_string()));
+            }
+            list = appended;
+        }
+
+        if !list.is_null() {
+            self.headers = list;
+            unsafe {
                 let code = curl_sys::curl_easy_setopt(
                     self.handle,
                     curl_sys::CURLOPT_HTTPHEADER,
@@ -279,13 +1000,28 @@ impl Curl {
                 );
                 check_code(code)?;
             }
+        }
+        Ok(())
+    }
 
-            // Perform the request
-            let code = curl_sys::curl_easy_perform(self.handle);
-            check_code(code)?;
+    /// Turn the result code of a finished transfer into a `Result`
+    pub(crate) fn transfer_result(&mut self, code: curl_sys::CURLcode) -> Result<()> {
+        // Cookies set before a failure are kept, like a browser would
+        let stored = self.store_cookie_jar();
+        if code == curl_sys::CURLE_ABORTED_BY_CALLBACK && self.is_aborted() {
+            return Err(CurlError::Cancelled);
         }
 
-        Ok(())
+        // An aborted upload is better explained by the reader's own error
+        let reader_error = match (&mut self.body_reader, &mut self.mime) {
+            (Some(body), _) => body.take_error(),
+            (None, Some(mime)) => mime.take_error(),
+            (None, None) => None,
+        };
+        if let Some(err) = reader_error {
+            return Err(CurlError::IoError(err));
+        }
+        check_code(code).and(stored)
     }
 
     pub fn perform_with_headers(
@@ -293,48 +1029,123 @@ impl Curl {
         body: &mut Vec<u8>,
         headers: &mut Vec<String>,
     ) -> Result<()> {
-        use std::os::raw::c_void;
-
         body.clear();
         headers.clear();
 
-        unsafe {
-            extern "C" fn header_func(
-                ptr: *mut c_char,
-                size: usize,
-                nmemb: usize,
-                userdata: *mut c_void,
-            ) -> usize {
-                let total_size = size * nmemb;
-                let headers = unsafe { &mut *(userdata as *mut Vec<String>) };
-
-                let header_line =
-                    unsafe { std::slice::from_raw_parts(ptr as *const u8, total_size) };
-
-                if let Ok(s) = std::str::from_utf8(header_line) {
-                    let trimmed = s.trim();
-                    if !trimmed.is_empty() {
-                        headers.push(trimmed.to_string());
-                    }
-                }
-                total_size
-            }
+        self.set_header_sink(headers as *mut Vec<String>);
+        let result = self.perform(body);
+        // Detach the callback so a later `perform` can't write into the dropped Vec
+        self.set_header_sink(ptr::null_mut());
 
-            curl_sys::curl_easy_setopt(
-                self.handle,
-                curl_sys::CURLOPT_HEADERFUNCTION,
-                header_func as *const c_void,
-            );
+        result
+    }
+
+    /// Point the header callback at `lines`, or detach it when null
+    pub(crate) fn set_header_sink(&mut self, lines: *mut Vec<String>) {
+        let func = if lines.is_null() {
+            ptr::null()
+        } else {
+            header_func as *const c_void
+        };
+        unsafe {
+            curl_sys::curl_easy_setopt(self.handle, curl_sys::CURLOPT_HEADERFUNCTION, func);
             curl_sys::curl_easy_setopt(
                 self.handle,
                 curl_sys::CURLOPT_HEADERDATA,
-                headers as *mut Vec<String> as *mut c_void,
+                lines as *mut c_void,
             );
+        }
+    }
+
+    /// Start the request and stream the response body as it arrives
+    ///
+    /// The handle is moved into the returned [`ResponseStream`], which drives
+    /// the transfer each time the next chunk is requested. Status and headers
+    /// are available as soon as the stream is returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::Curl;
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/stream/20").unwrap();
+    /// let stream = curl.perform_stream().unwrap();
+    /// println!("status {}", stream.status().unwrap());
+    /// for chunk in stream {
+    ///     println!("{} bytes", chunk.unwrap().len());
+    /// }
+    /// ```
+    pub fn perform_stream(self) -> Result<ResponseStream> {
+        ResponseStream::start(self)
+    }
+
+    /// Perform the request and collect status, headers, body and timings
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use hyprcurl::Curl;
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("https://httpbin.org/get").unwrap();
+    /// let response = curl.perform_response().unwrap();
+    /// println!("{} {}", response.status(), response.text().unwrap());
+    /// ```
+    pub fn perform_response(&mut self) -> Result<Response> {
+        let mut body = Vec::new();
+        let mut header_lines = Vec::new();
+        self.perform_with_headers(&mut body, &mut header_lines)?;
+        Response::from_transfer(self, body, &header_lines)
+    }
 
-            self.perform(body)?;
+    /// Get a long-valued transfer info field
+    pub fn getinfo_long(&self, info: CurlInfo) -> Result<i64> {
+        let mut value: i64 = 0;
+        unsafe {
+            let ret = curl_sys::curl_easy_getinfo(self.handle, info.to_raw(), &mut value);
+            check_code(ret)?;
         }
+        Ok(value)
+    }
 
-        Ok(())
+    /// Get a double-valued transfer info field
+    pub fn getinfo_double(&self, info: CurlInfo) -> Result<f64> {
+        let mut value: f64 = 0.0;
+        unsafe {
+            let ret = curl_sys::curl_easy_getinfo(self.handle, info.to_raw(), &mut value);
+            check_code(ret)?;
+        }
+        Ok(value)
+    }
+
+    /// Get a string-valued transfer info field
+    ///
+    /// Returns `None` when libcurl has no value for the field.
+    pub fn getinfo_str(&self, info: CurlInfo) -> Result<Option<String>> {
+        let mut value_ptr: *mut c_char = ptr::null_mut();
+        unsafe {
+            let ret = curl_sys::curl_easy_getinfo(self.handle, info.to_raw(), &mut value_ptr);
+            check_code(ret)?;
+
+            if value_ptr.is_null() {
+                return Ok(None);
+            }
+
+            Ok(Some(
+                CStr::from_ptr(value_ptr).to_string_lossy().into_owned(),
+            ))
+        }
+    }
+
+    /// Get the HTTP version negotiated for the last transfer
+    pub fn http_version(&self) -> Result<Option<HttpVersion>> {
+        Ok(HttpVersion::from_curl(
+            self.getinfo_long(CurlInfo::HttpVersion)?,
+        ))
+    }
+
+    /// URL set with [`set_url`](Self::set_url)
+    pub(crate) fn request_url(&self) -> Option<String> {
+        self.stored_url
+            .as_ref()
+            .map(|url| url.to_string_lossy().into_owned())
     }
 
     /// Get response code
@@ -364,6 +1175,12 @@ impl Curl {
 
     /// Get effective URL (after redirects)
     pub fn effective_url(&self) -> Result<String> {
+        // Setting the original URL back after a redirect chain also resets
+        // libcurl's effective URL
+        if let Some(url) = &self.final_url {
+            return Ok(url.clone());
+        }
+
         let mut url_ptr: *mut c_char = ptr::null_mut();
         unsafe {
             let ret = curl_sys::curl_easy_getinfo(
@@ -382,6 +1199,11 @@ impl Curl {
     }
 
     /// Reset the handle to default state
+    ///
+    /// Every option goes back to what [`new`](Self::new) sets, while live
+    /// connections, the DNS and TLS session caches, cookies and the
+    /// [`Share`] are kept, so the handle can be reused for an unrelated
+    /// request.
     pub fn reset(&mut self) {
         unsafe {
             curl_sys::curl_easy_reset(self.handle);
@@ -390,12 +1212,34 @@ impl Curl {
         // Clear stored strings
         self.stored_strings.clear();
         self.stored_url = None;
-        self.stored_postfields = None;
-        self.stored_headers.clear();
+        self.stored_body = None;
+        self.body_reader = None;
+        self.mime = None;
+        self.has_body = false;
+        self.method = None;
+        self.post_redirect = PostRedirect::default();
+        self.redirected = None;
+        self.final_url = None;
+        self.cookie_jar = None;
+        self.loaded_cookies.clear();
+        self.abort = None;
+        self.impersonation_gaps.clear();
+        // These options are all known to libcurl, so setting them again can
+        // only fail when it runs out of memory
+        let _ = self.apply_base_options();
+        if let Some(share) = self.share.take() {
+            let _ = self.set_share(&share);
+        }
     }
 
-    /// Cleanup headers
+    /// Drop all request headers
     fn cleanup_headers(&mut self) {
+        self.free_header_list();
+        self.header_map.clear();
+    }
+
+    /// Free the header list handed to libcurl by the last transfer
+    fn free_header_list(&mut self) {
         if !self.headers.is_null() {
             unsafe {
                 curl_sys::curl_slist_free_all(self.headers);
@@ -408,8 +1252,6 @@ impl Curl {
             }
             self.headers = ptr::null_mut();
         }
-        // Clear stored header strings
-        self.stored_headers.clear();
     }
 
     /// Get raw handle (for advanced use)
@@ -486,6 +1328,21 @@ impl Curl {
     /// This method configures the curl handle to impersonate a specific browser
     /// by setting appropriate User-Agent, headers, TLS ciphers, and other options.
     ///
+    /// The TLS handshake offers the browser's cipher suites, key exchange
+    /// groups, signature algorithms, ALPN protocols and TLS versions. With
+    /// libcurl-impersonate, HTTP/2 connections also open with the browser's
+    /// SETTINGS, WINDOW_UPDATE and PRIORITY frames, and send its
+    /// pseudo-header order (see [`Browser::http2_fingerprint`]).
+    ///
+    /// Parts of the fingerprint the linked libcurl can't reproduce (see
+    /// [`capabilities`](crate::capabilities)) are left out: options its TLS
+    /// backend doesn't take and groups it doesn't know are skipped, without
+    /// HTTP/2 the handle speaks HTTP/1.1, and `Accept-Encoding` only lists
+    /// encodings libcurl can decode. Each part left out is listed by
+    /// [`impersonation_gaps`](Self::impersonation_gaps); use
+    /// [`set_browser_impersonation_strict`](Self::set_browser_impersonation_strict)
+    /// to get an error instead.
+    ///
     /// # Arguments
     /// * `browser` - The browser type and version to impersonate
     ///
@@ -499,6 +1356,51 @@ impl Curl {
     /// curl.set_browser_impersonation(Browser::Chrome { version: 120 }).unwrap();
     /// ```
     pub fn set_browser_impersonation(&mut self, browser: Browser) -> Result<()> {
+        self.apply_browser(browser, false)
+    }
+
+    /// Impersonate `browser` completely or not at all
+    ///
+    /// Like [`set_browser_impersonation`](Self::set_browser_impersonation),
+    /// but fails with [`CurlError::FingerprintMismatch`], naming each missing
+    /// part, when the linked libcurl can't reproduce the whole fingerprint.
+    /// The handle is left unchanged then.
+    pub fn set_browser_impersonation_strict(&mut self, browser: Browser) -> Result<()> {
+        self.apply_browser(browser, true)
+    }
+
+    /// Parts of the browser's fingerprint the last
+    /// [`set_browser_impersonation`](Self::set_browser_impersonation) left
+    /// out, one per option, like
+    /// `"HTTP/2: libcurl was built without it"`
+    ///
+    /// Empty when the whole fingerprint is applied, and after a
+    /// [`reset`](Self::reset).
+    ///
+    /// # Examples
+    /// ```
+    /// use hyprcurl::{Browser, Curl};
+    ///
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_browser_impersonation(Browser::ChromeLatest).unwrap();
+    /// for gap in curl.impersonation_gaps() {
+    ///     eprintln!("not impersonated: {}", gap);
+    /// }
+    /// ```
+    pub fn impersonation_gaps(&self) -> &[String] {
+        &self.impersonation_gaps
+    }
+
+    fn apply_browser(&mut self, browser: Browser, strict: bool) -> Result<()> {
+        let plan = plan_browser(&browser, crate::capabilities());
+        if strict && !plan.gaps.is_empty() {
+            return Err(CurlError::FingerprintMismatch(format!(
+                "{:?}: {}",
+                browser,
+                plan.gaps.join("; ")
+            )));
+        }
+
         // Set User-Agent
         let user_agent = browser.user_agent();
         self.setopt_str(CurlOpt::UserAgent, &user_agent)?;
@@ -507,38 +1409,67 @@ impl Curl {
         self.cleanup_headers();
 
         // Add browser-specific headers
-        for (name, value) in browser.headers() {
-            let header = format!("{}: {}", name, value);
-            self.add_header(&header)?;
+        for (name, value) in plan.headers {
+            self.header_map.append(name, value)?;
         }
 
-        // Set TLS cipher suites (skip if not supported)
-        // Temporarily disabled - may cause issues with some libcurl versions
-        // let ciphers = browser.tls_ciphers();
-        // if self.setopt_str(CurlOpt::SslCipherList, ciphers).is_err() {
-        //     // Cipher list not supported, continue without it
-        // }
+        // Set TLS cipher suites
+        if !plan.ciphers.is_empty() {
+            self.setopt_str(CurlOpt::SslCipherList, &plan.ciphers.join(":"))?;
+        }
+        if !plan.tls13_ciphers.is_empty() {
+            self.setopt_str(CurlOpt::Tls13Ciphers, &plan.tls13_ciphers.join(":"))?;
+        }
 
-        // Set TLS curves (skip if not supported)
-        // Temporarily disabled - may cause issues with some libcurl versions
-        // let curves = browser.tls_curves();
-        // if self.setopt_str(CurlOpt::SslCurves, curves).is_err() {
-        //     // Curves not supported, continue without it
-        // }
+        // Set TLS curves
+        if !plan.curves.is_empty() {
+            self.setopt_str(CurlOpt::SslCurves, &plan.curves.join(":"))?;
+        }
 
-        // Configure HTTP version (most modern browsers use HTTP/2)
-        if self.set_http_version(HttpVersion::V2).is_err() {
-            // HTTP/2 not supported, fall back to HTTP/1.1
-            let _ = self.set_http_version(HttpVersion::V1_1);
+        if !plan.sigalgs.is_empty() {
+            if plan.sig_hash_algs {
+                // BoringSSL only knows the TLS 1.3 style names
+                let sigalgs: Vec<&str> = plan
+                    .sigalgs
+                    .iter()
+                    .map(|&sigalg| match sigalg {
+                        "ECDSA+SHA1" => "ecdsa_sha1",
+                        sigalg => sigalg,
+                    })
+                    .collect();
+                self.setopt_str(CurlOpt::SslSigHashAlgs, &sigalgs.join(","))?;
+            } else {
+                self.setopt_str(CurlOpt::SslSignatureAlgorithms, &plan.sigalgs.join(":"))?;
+            }
+        }
+
+        let (min_tls, max_tls) = browser.tls_versions();
+        self.set_tls_version(min_tls, Some(max_tls))?;
+
+        self.set_http_version(plan.http_version)?;
+        if plan.http2_fingerprint {
+            let http2 = browser.http2_fingerprint();
+            self.setopt_str(CurlOpt::Http2Settings, &http2.settings_string())?;
+            self.setopt_long(CurlOpt::Http2WindowUpdate, i64::from(http2.window_update))?;
+            if !http2.priority_frames.is_empty() {
+                self.setopt_str(CurlOpt::Http2Streams, &http2.priority_string())?;
+            }
+            self.setopt_str(CurlOpt::Http2PseudoHeadersOrder, http2.pseudo_header_order)?;
         }
 
         // Set additional browser-like options
-        self.setopt_long(CurlOpt::FollowLocation, 1)?; // Follow redirects like browsers
-        self.setopt_long(CurlOpt::MaxRedirs, 10)?; // Reasonable redirect limit
+        self.set_redirect_policy(RedirectPolicy::Limited(10))?; // Follow redirects like browsers
 
-        // Enable compression (browsers support this)
-        self.setopt_str(CurlOpt::AcceptEncoding, "gzip, deflate, br")?;
+        // Decode whatever the advertised encodings produce
+        match plan
+            .accept_encoding
+            .filter(|encodings| !encodings.is_empty())
+        {
+            Some(encodings) => self.setopt_str(CurlOpt::AcceptEncoding, &encodings)?,
+            None => self.unset_str(CurlOpt::AcceptEncoding)?,
+        }
 
+        self.impersonation_gaps = plan.gaps;
         Ok(())
     }
 
@@ -614,6 +1545,195 @@ impl Curl {
     }
 }
 
+/// The part of a browser's fingerprint a libcurl can reproduce
+pub(crate) struct BrowserPlan {
+    ciphers: Vec<&'static str>,
+    tls13_ciphers: Vec<&'static str>,
+    curves: Vec<&'static str>,
+    sigalgs: Vec<&'static str>,
+    /// Set the signature algorithms with libcurl-impersonate's
+    /// `CURLOPT_SSL_SIG_HASH_ALGS`
+    sig_hash_algs: bool,
+    http_version: HttpVersion,
+    /// Send the browser's HTTP/2 frames with libcurl-impersonate's options
+    http2_fingerprint: bool,
+    headers: Vec<(&'static str, String)>,
+    accept_encoding: Option<String>,
+    /// What is left out, each starting with the option it belongs to
+    pub(crate) gaps: Vec<String>,
+}
+
+/// Work out what of `browser`'s fingerprint a libcurl with `caps` can
+/// reproduce
+pub(crate) fn plan_browser(browser: &Browser, caps: &Capabilities) -> BrowserPlan {
+    let backend = caps
+        .ssl_backend
+        .as_deref()
+        .unwrap_or("a libcurl without TLS");
+    let mut gaps = Vec::new();
+
+    // OpenSSL-style backends take TLS 1.3 suites in a list of their own
+    let (mut tls13_ciphers, mut ciphers): (Vec<&str>, Vec<&str>) = browser
+        .tls_ciphers()
+        .split(':')
+        .partition(|cipher| cipher.starts_with("TLS_"));
+    if !caps.cipher_list {
+        gaps.push(format!(
+            "CURLOPT_SSL_CIPHER_LIST: not supported by {}",
+            backend
+        ));
+        ciphers.clear();
+    }
+    if !caps.tls13_ciphers {
+        gaps.push(format!(
+            "CURLOPT_TLS13_CIPHERS: not supported by {}",
+            backend
+        ));
+        tls13_ciphers.clear();
+    }
+
+    // One unknown group fails every handshake, so offer only known ones
+    let (curves, missing): (Vec<&str>, Vec<&str>) = browser
+        .tls_curves()
+        .split(':')
+        .partition(|curve| caps.supports_curve(curve));
+    for curve in missing {
+        gaps.push(format!(
+            "CURLOPT_SSL_EC_CURVES: {} isn't supported by {}",
+            curve, backend
+        ));
+    }
+
+    let (mut sigalgs, missing): (Vec<&str>, Vec<&str>) = browser
+        .tls_sigalgs()
+        .split(':')
+        .partition(|sigalg| caps.supports_sigalg(sigalg));
+    if !caps.signature_algorithms && !caps.sig_hash_algs {
+        gaps.push(format!(
+            "CURLOPT_SSL_SIGNATURE_ALGORITHMS: not supported by {}",
+            backend
+        ));
+        sigalgs.clear();
+    } else {
+        for sigalg in missing {
+            gaps.push(format!(
+                "CURLOPT_SSL_SIGNATURE_ALGORITHMS: {} isn't offered by {}",
+                sigalg, backend
+            ));
+        }
+    }
+
+    let (min_tls, max_tls) = browser.tls_versions();
+    for version in [
+        TlsVersion::V1_0,
+        TlsVersion::V1_1,
+        TlsVersion::V1_2,
+        TlsVersion::V1_3,
+    ] {
+        if (min_tls..=max_tls).contains(&version) && !caps.supports_tls_version(version) {
+            gaps.push(format!(
+                "CURLOPT_SSLVERSION: {} isn't offered by {}",
+                version, backend
+            ));
+        }
+    }
+
+    let http_version = alpn_http_version(browser.alpn(), caps, &mut gaps);
+
+    // Plain libcurl sends nghttp2's SETTINGS and its own header order
+    let http2 = http_version == HttpVersion::V2;
+    if http2 && !caps.http2_fingerprint {
+        for option in [
+            "CURLOPT_HTTP2_SETTINGS",
+            "CURLOPT_HTTP2_WINDOW_UPDATE",
+            "CURLOPT_HTTP2_STREAMS",
+            "CURLOPT_HTTP2_PSEUDO_HEADERS_ORDER",
+        ] {
+            gaps.push(format!("{}: needs libcurl-impersonate", option));
+        }
+    }
+
+    // Advertising an encoding libcurl can't decode would hand the caller
+    // a compressed body
+    let mut headers = browser.headers();
+    let mut accept_encoding = None;
+    for (name, value) in &mut headers {
+        if !name.eq_ignore_ascii_case("Accept-Encoding") {
+            continue;
+        }
+        let (usable, missing): (Vec<&str>, Vec<&str>) = value
+            .split(',')
+            .map(str::trim)
+            .partition(|encoding| caps.supports_encoding(encoding));
+        for encoding in missing {
+            gaps.push(format!(
+                "content encoding {}: libcurl can't decode it",
+                encoding
+            ));
+        }
+        *value = usable.join(", ");
+        accept_encoding = Some(value.clone());
+    }
+    headers.retain(|(_, value)| !value.is_empty());
+
+    BrowserPlan {
+        ciphers,
+        tls13_ciphers,
+        curves,
+        sigalgs,
+        sig_hash_algs: !caps.signature_algorithms && caps.sig_hash_algs,
+        http_version,
+        http2_fingerprint: http2 && caps.http2_fingerprint,
+        headers,
+        accept_encoding,
+        gaps,
+    }
+}
+
+/// HTTP version whose ALPN offer matches `alpn`
+///
+/// libcurl offers h2 over ALPN exactly when it may speak HTTP/2, and only
+/// http/1.1 when held to HTTP/1.1.
+fn alpn_http_version(alpn: &[&str], caps: &Capabilities, gaps: &mut Vec<String>) -> HttpVersion {
+    if !alpn.contains(&"h2") {
+        HttpVersion::V1_1
+    } else if caps.http2 {
+        HttpVersion::V2
+    } else {
+        gaps.push("ALPN: h2 can't be offered without HTTP/2".to_string());
+        gaps.push("HTTP/2: libcurl was built without it".to_string());
+        HttpVersion::V1_1
+    }
+}
+
+/// Milliseconds for a `*_MS` option; zero would mean "no timeout" to libcurl
+fn duration_to_ms(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis())
+        .unwrap_or(i64::MAX)
+        .max(1)
+}
+
+/// Header callback collecting trimmed, non-empty header lines into a `Vec<String>`
+extern "C" fn header_func(
+    ptr: *mut c_char,
+    size: usize,
+    nmemb: usize,
+    userdata: *mut c_void,
+) -> usize {
+    let total_size = size * nmemb;
+    let headers = unsafe { &mut *(userdata as *mut Vec<String>) };
+
+    let header_line = unsafe { std::slice::from_raw_parts(ptr as *const u8, total_size) };
+
+    if let Ok(s) = std::str::from_utf8(header_line) {
+        let trimmed = s.trim();
+        if !trimmed.is_empty() {
+            headers.push(trimmed.to_string());
+        }
+    }
+    total_size
+}
+
 impl Drop for Curl {
     fn drop(&mut self) {
         self.cleanup_headers();
@@ -649,4 +1769,197 @@ mod tests {
         let mut curl = Curl::new().unwrap();
         assert!(curl.add_header("User-Agent: test").is_ok());
     }
+
+    #[test]
+    fn test_impersonate_needs_libcurl_impersonate() {
+        let mut curl = Curl::new().unwrap();
+        let result = curl.impersonate("chrome120", true);
+        if crate::impersonate::curl_easy_impersonate().is_some() {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(result, Err(CurlError::ImpersonateUnavailable)));
+        }
+    }
+
+    /// A libcurl-impersonate build on BoringSSL, which can do everything
+    fn full_capabilities() -> Capabilities {
+        Capabilities {
+            version: "8.17.0".to_string(),
+            ssl_backend: Some("BoringSSL".to_string()),
+            http2: true,
+            http3: false,
+            libz: true,
+            cipher_list: true,
+            tls13_ciphers: true,
+            signature_algorithms: false,
+            http2_fingerprint: true,
+            sig_hash_algs: true,
+            brotli: true,
+            zstd: true,
+            idn: true,
+            impersonate: true,
+            protocols: vec!["http".to_string(), "https".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_plan_browser_without_gaps() {
+        let plan = plan_browser(&Browser::ChromeLatest, &full_capabilities());
+        assert!(plan.gaps.is_empty(), "{:?}", plan.gaps);
+        assert_eq!(plan.curves.last(), Some(&"X25519Kyber768Draft00"));
+        assert_eq!(plan.tls13_ciphers.len(), 3);
+        assert!(plan.sig_hash_algs);
+        assert_eq!(plan.http_version, HttpVersion::V2);
+        assert!(plan.http2_fingerprint);
+        assert_eq!(plan.accept_encoding.as_deref(), Some("gzip, deflate, br"));
+    }
+
+    #[test]
+    fn test_plan_browser_names_each_gap() {
+        // Plain libcurl on OpenSSL 3.0, without HTTP/2 or brotli, on a
+        // backend that takes no TLS 1.3 suites
+        let mut caps = full_capabilities();
+        caps.ssl_backend = Some("OpenSSL/3.0.13".to_string());
+        caps.signature_algorithms = true;
+        caps.sig_hash_algs = false;
+        caps.http2_fingerprint = false;
+        caps.tls13_ciphers = false;
+        caps.http2 = false;
+        caps.brotli = false;
+        let plan = plan_browser(&Browser::ChromeLatest, &caps);
+        assert_eq!(
+            plan.gaps,
+            [
+                "CURLOPT_TLS13_CIPHERS: not supported by OpenSSL/3.0.13",
+                "CURLOPT_SSL_EC_CURVES: X25519Kyber768Draft00 isn't supported by OpenSSL/3.0.13",
+                "ALPN: h2 can't be offered without HTTP/2",
+                "HTTP/2: libcurl was built without it",
+                "content encoding br: libcurl can't decode it",
+            ]
+        );
+        assert!(plan.tls13_ciphers.is_empty());
+        assert_eq!(plan.curves, ["X25519", "P-256", "P-384", "P-521"]);
+        assert_eq!(plan.http_version, HttpVersion::V1_1);
+        assert!(!plan.http2_fingerprint);
+        assert_eq!(plan.accept_encoding.as_deref(), Some("gzip, deflate"));
+
+        // HTTP/2 without libcurl-impersonate, and a browser still offering
+        // SHA-1 signatures and TLS 1.0
+        caps.http2 = true;
+        caps.tls13_ciphers = true;
+        caps.brotli = true;
+        let plan = plan_browser(&Browser::SafariLatest, &caps);
+        assert_eq!(
+            plan.gaps,
+            [
+                "CURLOPT_SSL_SIGNATURE_ALGORITHMS: ECDSA+SHA1 isn't offered by OpenSSL/3.0.13",
+                "CURLOPT_SSL_SIGNATURE_ALGORITHMS: rsa_pkcs1_sha1 isn't offered by OpenSSL/3.0.13",
+                "CURLOPT_SSLVERSION: TLS 1.0 isn't offered by OpenSSL/3.0.13",
+                "CURLOPT_SSLVERSION: TLS 1.1 isn't offered by OpenSSL/3.0.13",
+                "CURLOPT_HTTP2_SETTINGS: needs libcurl-impersonate",
+                "CURLOPT_HTTP2_WINDOW_UPDATE: needs libcurl-impersonate",
+                "CURLOPT_HTTP2_STREAMS: needs libcurl-impersonate",
+                "CURLOPT_HTTP2_PSEUDO_HEADERS_ORDER: needs libcurl-impersonate",
+            ]
+        );
+        assert!(!plan.sigalgs.iter().any(|sigalg| sigalg.contains("SHA1")));
+        assert_eq!(plan.http_version, HttpVersion::V2);
+
+        // No TLS at all
+        caps.ssl_backend = None;
+        caps.cipher_list = false;
+        caps.tls13_ciphers = false;
+        caps.signature_algorithms = false;
+        let plan = plan_browser(&Browser::FirefoxLatest, &caps);
+        assert_eq!(
+            plan.gaps[..3],
+            [
+                "CURLOPT_SSL_CIPHER_LIST: not supported by a libcurl without TLS",
+                "CURLOPT_TLS13_CIPHERS: not supported by a libcurl without TLS",
+                "CURLOPT_SSL_EC_CURVES: X25519 isn't supported by a libcurl without TLS",
+            ]
+        );
+        assert!(plan.ciphers.is_empty() && plan.curves.is_empty() && plan.sigalgs.is_empty());
+    }
+
+    #[test]
+    fn test_alpn_picks_http_version() {
+        let mut caps = full_capabilities();
+        let mut gaps = Vec::new();
+        let both = ["h2", "http/1.1"];
+        assert_eq!(alpn_http_version(&both, &caps, &mut gaps), HttpVersion::V2);
+        assert_eq!(
+            alpn_http_version(&["http/1.1"], &caps, &mut gaps),
+            HttpVersion::V1_1
+        );
+        assert!(gaps.is_empty());
+
+        caps.http2 = false;
+        assert_eq!(
+            alpn_http_version(&["http/1.1"], &caps, &mut gaps),
+            HttpVersion::V1_1
+        );
+        assert!(gaps.is_empty());
+        assert_eq!(
+            alpn_http_version(&both, &caps, &mut gaps),
+            HttpVersion::V1_1
+        );
+        assert_eq!(
+            gaps,
+            [
+                "ALPN: h2 can't be offered without HTTP/2",
+                "HTTP/2: libcurl was built without it",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_impersonation_fails_on_any_gap() {
+        let browser = Browser::ChromeLatest;
+        let gaps = plan_browser(&browser, crate::capabilities()).gaps;
+        let mut strict = Curl::new().unwrap();
+        match strict.set_browser_impersonation_strict(browser.clone()) {
+            Ok(()) => assert!(gaps.is_empty()),
+            Err(CurlError::FingerprintMismatch(message)) => {
+                assert_eq!(message, format!("{:?}: {}", browser, gaps.join("; ")));
+                assert!(strict.headers().is_empty());
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_impersonation_reports_gaps_without_strict() {
+        let caps = crate::capabilities();
+        let browser = Browser::SafariLatest;
+        let mut curl = Curl::new().unwrap();
+        curl.set_browser_impersonation(browser.clone()).unwrap();
+        let gaps = curl.impersonation_gaps().to_vec();
+        assert_eq!(gaps, caps.impersonation_gaps(&browser));
+        let has = |gap: &str| gaps.iter().any(|g| g == gap);
+        assert_eq!(has("HTTP/2: libcurl was built without it"), !caps.http2);
+        assert_eq!(
+            has("content encoding br: libcurl can't decode it"),
+            !caps.brotli
+        );
+        if let Some(backend) = &caps.ssl_backend {
+            assert_eq!(
+                has(&format!(
+                    "CURLOPT_SSLVERSION: TLS 1.0 isn't offered by {}",
+                    backend
+                )),
+                !caps.supports_tls_version(TlsVersion::V1_0)
+            );
+        }
+
+        curl.reset();
+        assert!(curl.impersonation_gaps().is_empty());
+
+        // Requests impersonate through the same call
+        let curl = crate::Request::get("https://example.com")
+            .impersonate(browser)
+            .into_curl()
+            .unwrap();
+        assert_eq!(curl.impersonation_gaps(), gaps);
+    }
 }
@@ -26,18 +26,60 @@ pub enum CurlError {
         message: String,
     },
 
+    /// Share operation failed
+    #[error("Curl share error {code}: {message}")]
+    ShareError {
+        code: curl_sys::CURLSHcode,
+        message: String,
+    },
+
+    /// Server answered with a 4xx or 5xx status
+    #[error("HTTP status {status} for {url}")]
+    HttpStatus { status: u16, url: String },
+
+    /// The response sink passed to `perform_into` failed; the transfer was aborted
+    #[error("Response sink error: {0}")]
+    SinkError(std::io::Error),
+
+    /// The transfer was stopped through its [`AbortHandle`](crate::AbortHandle)
+    #[error("Transfer was cancelled")]
+    Cancelled,
+
     /// Invalid URL
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    /// Header name or value that can't be sent
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+
+    /// Cookie that can't be stored, or a cookie file that can't be parsed
+    #[error("Invalid cookie: {0}")]
+    InvalidCookie(String),
+
     /// Invalid option value
     #[error("Invalid option value: {0}")]
     InvalidOption(String),
 
+    /// libcurl-impersonate is needed, but the linked libcurl isn't it
+    #[error(
+        "libcurl-impersonate is not available: the linked libcurl has no curl_easy_impersonate"
+    )]
+    ImpersonateUnavailable,
+
+    /// Strict impersonation found parts of the browser's fingerprint that the
+    /// linked libcurl can't reproduce
+    #[error("Impersonation would not match the browser: {0}")]
+    FingerprintMismatch(String),
+
     /// WebSocket error
     #[error("WebSocket error: {0}")]
     WebSocketError(String),
 
+    /// Server-sent events stream that can't be read
+    #[error("Event stream error: {0}")]
+    EventStreamError(String),
+
     /// UTF-8 conversion error
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::str::Utf8Error),
@@ -46,6 +88,20 @@ pub enum CurlError {
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// The request body could not be serialized to JSON
+    #[cfg(feature = "json")]
+    #[error("Failed to serialize JSON body: {0}")]
+    JsonSerialize(serde_json::Error),
+
+    /// The response body is not valid JSON for the requested type
+    #[cfg(feature = "json")]
+    #[error("Failed to decode JSON response: {source} (body starts with {snippet:?})")]
+    JsonDecode {
+        source: serde_json::Error,
+        /// Beginning of the response body, for diagnostics
+        snippet: String,
+    },
+
     /// Handle is closed
     #[error("Cannot perform operation on closed handle")]
     HandleClosed,
@@ -68,11 +124,40 @@ impl CurlError {
         CurlError::CurlCode { code, message }
     }
 
+    /// Create a CurlError from a CURLMcode
+    pub fn from_multi_code(code: curl_sys::CURLMcode) -> Self {
+        let message = unsafe {
+            let msg_ptr = curl_sys::curl_multi_strerror(code);
+            std::ffi::CStr::from_ptr(msg_ptr)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        CurlError::MultiError { code, message }
+    }
+
+    /// Create a CurlError from a CURLSHcode
+    pub fn from_share_code(code: curl_sys::CURLSHcode) -> Self {
+        let message = unsafe {
+            let msg_ptr = curl_sys::curl_share_strerror(code);
+            std::ffi::CStr::from_ptr(msg_ptr)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        CurlError::ShareError { code, message }
+    }
+
     /// Get the curl error code if available
     pub fn code(&self) -> Option<u32> {
         match self {
-            CurlError::CurlCode { code, .. } => u32::try_from(*code).ok(),
+            // CURLcode and CURLSHcode are unsigned on most targets but
+            // signed on Windows
+            #[allow(clippy::unnecessary_cast)]
+            CurlError::CurlCode { code, .. } => Some(*code as u32),
             CurlError::MultiError { code, .. } => Some(*code as u32),
+            #[allow(clippy::unnecessary_cast)]
+            CurlError::ShareError { code, .. } => Some(*code as u32),
             _ => None,
         }
     }
@@ -86,3 +171,12 @@ pub(crate) fn check_code(code: curl_sys::CURLcode) -> Result<()> {
         Err(CurlError::from_curl_code(code))
     }
 }
+
+/// Check a CURLMcode and convert to Result
+pub(crate) fn check_multi_code(code: curl_sys::CURLMcode) -> Result<()> {
+    if code == curl_sys::CURLM_OK {
+        Ok(())
+    } else {
+        Err(CurlError::from_multi_code(code))
+    }
+}
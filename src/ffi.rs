@@ -0,0 +1,195 @@
+//! Raw libcurl definitions that are missing from `curl-sys`
+//!
+//! `curl-sys` tracks an older subset of the libcurl API. Everything here is
+//! present in the bundled libcurl we link against, it just has no binding yet.
+
+#![allow(non_camel_case_types, clippy::upper_case_acronyms, dead_code)]
+
+use curl_sys::{
+    curl_off_t, curl_read_callback, curl_seek_callback, CURLcode, CURLoption, CURL, CURLINFO,
+    CURLINFO_LONG, CURLOPTTYPE_FUNCTIONPOINT, CURLOPTTYPE_LONG, CURLOPTTYPE_OBJECTPOINT,
+    CURLOPT_PROGRESSDATA,
+};
+use libc::size_t;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+/// Negotiated HTTP version of the last transfer (`CURL_HTTP_VERSION_*`)
+pub const CURLINFO_HTTP_VERSION: CURLINFO = CURLINFO_LONG + 46;
+
+/// `CURLINFO` type for `curl_socket_t` values
+pub const CURLINFO_SOCKET: CURLINFO = 0x500000;
+
+/// Socket of the last transfer's connection, which replaced
+/// `CURLINFO_LASTSOCKET`
+pub const CURLINFO_ACTIVESOCKET: CURLINFO = CURLINFO_SOCKET + 44;
+
+/// Keep proxy CONNECT responses out of the header callback
+pub const CURLOPT_SUPPRESS_CONNECT_HEADERS: CURLoption = CURLOPTTYPE_LONG + 265;
+
+/// Colon-separated TLS 1.3 cipher suites, kept apart from
+/// `CURLOPT_SSL_CIPHER_LIST` by the OpenSSL-style backends
+pub const CURLOPT_TLS13_CIPHERS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 276;
+
+/// Colon-separated key exchange groups offered in the TLS handshake
+pub const CURLOPT_SSL_EC_CURVES: CURLoption = CURLOPTTYPE_OBJECTPOINT + 298;
+
+/// Colon-separated signature algorithms offered in the TLS handshake
+pub const CURLOPT_SSL_SIGNATURE_ALGORITHMS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 328;
+
+/// Negotiate the application protocol with ALPN
+pub const CURLOPT_SSL_ENABLE_ALPN: CURLoption = CURLOPTTYPE_LONG + 226;
+
+/// `CURLOPT_SSLVERSION` upper bound of "whatever the TLS library supports",
+/// or'ed with a minimum version; `CURL_SSLVERSION_MAX_TLSv1_x` is
+/// `CURL_SSLVERSION_TLSv1_x` shifted the same way
+pub const CURL_SSLVERSION_MAX_DEFAULT: i64 = 1 << 16;
+
+/// Comma-separated signature algorithms, libcurl-impersonate's option
+/// predating `CURLOPT_SSL_SIGNATURE_ALGORITHMS`
+pub const CURLOPT_SSL_SIG_HASH_ALGS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 1001;
+
+/// libcurl-impersonate: order of the pseudo-headers, like `"masp"` for
+/// `:method`, `:authority`, `:scheme`, `:path`
+pub const CURLOPT_HTTP2_PSEUDO_HEADERS_ORDER: CURLoption = CURLOPTTYPE_OBJECTPOINT + 1005;
+
+/// libcurl-impersonate: SETTINGS frame entries, like `"1:65536;4:6291456"`
+pub const CURLOPT_HTTP2_SETTINGS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 1006;
+
+/// libcurl-impersonate: connection WINDOW_UPDATE increment sent after
+/// SETTINGS
+pub const CURLOPT_HTTP2_WINDOW_UPDATE: CURLoption = CURLOPTTYPE_LONG + 1008;
+
+/// libcurl-impersonate: PRIORITY frames sent when the connection opens, as
+/// `stream:exclusive:depends_on:weight` joined with commas
+pub const CURLOPT_HTTP2_STREAMS: CURLoption = CURLOPTTYPE_OBJECTPOINT + 1010;
+
+/// Progress callback taking `curl_off_t` counts, which replaced
+/// `CURLOPT_PROGRESSFUNCTION`
+pub const CURLOPT_XFERINFOFUNCTION: CURLoption = CURLOPTTYPE_FUNCTIONPOINT + 219;
+
+/// Pointer passed to the xferinfo callback; an alias of `CURLOPT_PROGRESSDATA`
+pub const CURLOPT_XFERINFODATA: CURLoption = CURLOPT_PROGRESSDATA;
+
+/// `curl_easy_impersonate` from libcurl-impersonate, which only exists
+/// there and is looked up at runtime
+pub type curl_easy_impersonate_fn = unsafe extern "C" fn(
+    curl: *mut CURL,
+    target: *const c_char,
+    default_headers: c_int,
+) -> CURLcode;
+
+/// Opaque MIME structure built by `curl_mime_init`
+pub enum curl_mime {}
+
+/// Opaque part of a [`curl_mime`] structure
+pub enum curl_mimepart {}
+
+/// `curl_mime *` to send as a multipart/form-data body
+pub const CURLOPT_MIMEPOST: CURLoption = CURLOPTTYPE_OBJECTPOINT + 269;
+
+/// Size argument telling `curl_mime_data` to use `strlen`
+pub const CURL_ZERO_TERMINATED: size_t = size_t::MAX;
+
+pub type curl_free_callback = Option<extern "C" fn(*mut c_void)>;
+
+extern "C" {
+    pub fn curl_mime_init(easy: *mut CURL) -> *mut curl_mime;
+    pub fn curl_mime_free(mime: *mut curl_mime);
+    pub fn curl_mime_addpart(mime: *mut curl_mime) -> *mut curl_mimepart;
+    pub fn curl_mime_name(part: *mut curl_mimepart, name: *const c_char) -> CURLcode;
+    pub fn curl_mime_filename(part: *mut curl_mimepart, filename: *const c_char) -> CURLcode;
+    pub fn curl_mime_type(part: *mut curl_mimepart, mimetype: *const c_char) -> CURLcode;
+    pub fn curl_mime_data(
+        part: *mut curl_mimepart,
+        data: *const c_char,
+        datasize: size_t,
+    ) -> CURLcode;
+    pub fn curl_mime_filedata(part: *mut curl_mimepart, filename: *const c_char) -> CURLcode;
+    pub fn curl_mime_data_cb(
+        part: *mut curl_mimepart,
+        datasize: curl_off_t,
+        readfunc: curl_read_callback,
+        seekfunc: curl_seek_callback,
+        freefunc: curl_free_callback,
+        arg: *mut c_void,
+    ) -> CURLcode;
+}
+
+/// Opaque URL handle of the `curl_url` API
+pub enum CURLU {}
+
+pub type CURLUcode = c_int;
+pub type CURLUPart = c_int;
+
+pub const CURLUE_OK: CURLUcode = 0;
+
+pub const CURLUPART_URL: CURLUPart = 0;
+pub const CURLUPART_SCHEME: CURLUPart = 1;
+pub const CURLUPART_USER: CURLUPart = 2;
+pub const CURLUPART_PASSWORD: CURLUPart = 3;
+pub const CURLUPART_OPTIONS: CURLUPart = 4;
+pub const CURLUPART_HOST: CURLUPart = 5;
+pub const CURLUPART_PORT: CURLUPart = 6;
+pub const CURLUPART_PATH: CURLUPart = 7;
+pub const CURLUPART_QUERY: CURLUPart = 8;
+pub const CURLUPART_FRAGMENT: CURLUPart = 9;
+
+pub const CURLU_DEFAULT_PORT: c_uint = 1 << 0;
+pub const CURLU_NON_SUPPORT_SCHEME: c_uint = 1 << 3;
+pub const CURLU_URLENCODE: c_uint = 1 << 7;
+pub const CURLU_APPENDQUERY: c_uint = 1 << 8;
+pub const CURLU_GUESS_SCHEME: c_uint = 1 << 9;
+
+extern "C" {
+    pub fn curl_url() -> *mut CURLU;
+    pub fn curl_url_cleanup(handle: *mut CURLU);
+    pub fn curl_url_dup(handle: *const CURLU) -> *mut CURLU;
+    pub fn curl_url_get(
+        handle: *const CURLU,
+        what: CURLUPart,
+        part: *mut *mut c_char,
+        flags: c_uint,
+    ) -> CURLUcode;
+    pub fn curl_url_set(
+        handle: *mut CURLU,
+        what: CURLUPart,
+        part: *const c_char,
+        flags: c_uint,
+    ) -> CURLUcode;
+    pub fn curl_url_strerror(code: CURLUcode) -> *const c_char;
+}
+
+/// `CURLWS_*` bits for `CURLOPT_WS_OPTIONS`
+pub const CURLOPT_WS_OPTIONS: CURLoption = CURLOPTTYPE_LONG + 320;
+
+/// Hand PING frames to `curl_ws_recv` instead of answering them
+pub const CURLWS_NOAUTOPONG: i64 = 1 << 1;
+
+/// Metadata of the frame data returned by `curl_ws_recv`
+#[repr(C)]
+pub struct curl_ws_frame {
+    pub age: c_int,
+    pub flags: c_int,
+    pub offset: curl_off_t,
+    pub bytesleft: curl_off_t,
+    pub len: size_t,
+}
+
+extern "C" {
+    pub fn curl_ws_recv(
+        curl: *mut CURL,
+        buffer: *mut c_void,
+        buflen: size_t,
+        recv: *mut size_t,
+        metap: *mut *const curl_ws_frame,
+    ) -> CURLcode;
+    pub fn curl_ws_send(
+        curl: *mut CURL,
+        buffer: *const c_void,
+        buflen: size_t,
+        sent: *mut size_t,
+        fragsize: curl_off_t,
+        flags: c_uint,
+    ) -> CURLcode;
+    pub fn curl_ws_meta(curl: *mut CURL) -> *const curl_ws_frame;
+}
@@ -0,0 +1,276 @@
+//! HTTP header map used for request and response headers
+
+use crate::error::{CurlError, Result};
+use std::fmt;
+
+/// Case-insensitive, multi-valued map of HTTP headers
+///
+/// Headers keep the order they were added in, and a name may appear more
+/// than once (`Set-Cookie`, for example). Names and values are validated on
+/// insertion, so a map can't smuggle extra header lines into a request.
+///
+/// # Examples
+/// ```
+/// use hyprcurl::HeaderMap;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("Accept", "application/json").unwrap();
+/// headers.append("X-Tag", "a").unwrap();
+/// headers.append("x-tag", "b").unwrap();
+///
+/// assert_eq!(headers.get("accept"), Some("application/json"));
+/// assert_eq!(headers.get_all("X-TAG").collect::<Vec<_>>(), ["a", "b"]);
+/// assert!(headers.insert("Bad\r\nName", "x").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a header, replacing every existing value with the same name
+    ///
+    /// The header keeps the position of its first occurrence.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) -> Result<()> {
+        let (name, value) = validate(name.into(), value.into())?;
+        self.replace(name, value);
+        Ok(())
+    }
+
+    fn replace(&mut self, name: String, value: String) {
+        let Some(first) = self.position(&name) else {
+            self.entries.push((name, value));
+            return;
+        };
+        let mut index = 0;
+        self.entries.retain(|(k, _)| {
+            let keep = index <= first || !k.eq_ignore_ascii_case(&name);
+            index += 1;
+            keep
+        });
+        self.entries[first] = (name, value);
+    }
+
+    /// Override headers with the ones from `other`
+    ///
+    /// A replaced header keeps its position, since header order is part of
+    /// a browser fingerprint; repeated names in `other` are all kept.
+    pub(crate) fn merge(&mut self, other: HeaderMap) {
+        let mut replaced: Vec<String> = Vec::new();
+        for (name, value) in other.entries {
+            if replaced.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                self.entries.push((name, value));
+            } else {
+                replaced.push(name.clone());
+                self.replace(name, value);
+            }
+        }
+    }
+
+    /// Add a header, keeping existing values with the same name
+    pub fn append(&mut self, name: impl Into<String>, value: impl Into<String>) -> Result<()> {
+        let (name, value) = validate(name.into(), value.into())?;
+        self.entries.push((name, value));
+        Ok(())
+    }
+
+    /// Add a header received from a server without validating it
+    pub(crate) fn append_unchecked(&mut self, name: String, value: String) {
+        self.entries.push((name, value));
+    }
+
+    /// First value of a header (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.position(name).map(|i| self.entries[i].1.as_str())
+    }
+
+    /// Every value of a header, in order (case-insensitive)
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether the header is present (case-insensitive)
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// Remove every value of a header, returning the first one
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let first = self.position(name)?;
+        let value = self.entries.remove(first).1;
+        self.entries.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        Some(value)
+    }
+
+    /// Number of header lines, counting repeated names separately
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map has no headers
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove every header
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterate over `(name, value)` pairs in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Header names in insertion order, repeated names included
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| k.as_str())
+    }
+
+    /// Header lines in the format libcurl expects for `CURLOPT_HTTPHEADER`
+    ///
+    /// An empty value is passed as `Name:`, which tells libcurl to drop a
+    /// header it would otherwise add itself (such as `Accept`).
+    pub(crate) fn to_curl_lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v).trim_end().to_string())
+            .collect()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(k, _)| k.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for HeaderMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.entries {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderMap {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Box<dyn Iterator<Item = (&'a str, &'a str)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl IntoIterator for HeaderMap {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Whether `c` may appear in a header name (an RFC 9110 token)
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn validate(name: String, value: String) -> Result<(String, String)> {
+    if name.is_empty() || !name.chars().all(is_token_char) {
+        return Err(CurlError::InvalidHeader(format!(
+            "invalid header name {:?}",
+            name
+        )));
+    }
+    if value.contains(['\r', '\n', '\0']) {
+        return Err(CurlError::InvalidHeader(format!(
+            "invalid value for header {}",
+            name
+        )));
+    }
+    Ok((name, value.trim().to_string()))
+}
+
+/// Split a `Name: value` line into its validated parts
+pub(crate) fn parse_header_line(line: &str) -> Result<(String, String)> {
+    let (name, value) = line.split_once(':').ok_or_else(|| {
+        CurlError::InvalidHeader(format!("expected \"Name: value\", got {:?}", line))
+    })?;
+    validate(name.trim().to_string(), value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_replaces_all_values_in_place() {
+        let mut headers = HeaderMap::new();
+        headers.append("Accept", "text/html").unwrap();
+        headers.append("X-A", "1").unwrap();
+        headers.append("accept", "*/*").unwrap();
+        headers.insert("ACCEPT", "application/json").unwrap();
+
+        let pairs: Vec<_> = headers.iter().collect();
+        assert_eq!(pairs, [("ACCEPT", "application/json"), ("X-A", "1")]);
+        assert_eq!(headers.remove("x-a"), Some("1".to_string()));
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_positions() {
+        let mut headers = HeaderMap::new();
+        headers.append("User-Agent", "browser").unwrap();
+        headers.append("Accept", "text/html").unwrap();
+        headers.append("Accept-Language", "en").unwrap();
+
+        let mut overrides = HeaderMap::new();
+        overrides.append("accept", "application/json").unwrap();
+        overrides.append("X-Tag", "a").unwrap();
+        overrides.append("X-Tag", "b").unwrap();
+        headers.merge(overrides);
+
+        let pairs: Vec<_> = headers.iter().collect();
+        assert_eq!(
+            pairs,
+            [
+                ("User-Agent", "browser"),
+                ("accept", "application/json"),
+                ("Accept-Language", "en"),
+                ("X-Tag", "a"),
+                ("X-Tag", "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut headers = HeaderMap::new();
+        assert!(headers.append("", "x").is_err());
+        assert!(headers.append("Bad Name", "x").is_err());
+        assert!(headers.append("X-Evil", "a\r\nHost: attacker").is_err());
+        assert!(parse_header_line("no colon").is_err());
+        assert_eq!(
+            parse_header_line("X-Ok:  spaced value ").unwrap(),
+            ("X-Ok".to_string(), "spaced value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_curl_lines() {
+        let mut headers = HeaderMap::new();
+        headers.append("X-A", "1").unwrap();
+        headers.append("Accept", "").unwrap();
+        assert_eq!(headers.to_curl_lines(), ["X-A: 1", "Accept:"]);
+    }
+}
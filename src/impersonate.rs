@@ -0,0 +1,64 @@
+//! `curl_easy_impersonate` from libcurl-impersonate, looked up at runtime
+//!
+//! Linking against it directly would make libcurl-impersonate a hard
+//! requirement, so the symbol is resolved in whichever libcurl provides
+//! `curl_easy_init`, and is missing with a plain libcurl.
+
+use crate::ffi::curl_easy_impersonate_fn;
+use std::sync::OnceLock;
+
+/// `curl_easy_impersonate` of the libcurl our handles come from, if it has
+/// one
+pub(crate) fn curl_easy_impersonate() -> Option<curl_easy_impersonate_fn> {
+    static FUNCTION: OnceLock<Option<curl_easy_impersonate_fn>> = OnceLock::new();
+    *FUNCTION.get_or_init(lookup)
+}
+
+#[cfg(unix)]
+fn lookup() -> Option<curl_easy_impersonate_fn> {
+    use std::os::raw::{c_char, c_void};
+
+    const NAME: &[u8] = b"curl_easy_impersonate\0";
+    let curl_easy_init = curl_sys::curl_easy_init as *const c_void;
+
+    unsafe {
+        // Search the object holding libcurl, which `RTLD_DEFAULT` misses
+        // when it was loaded with `RTLD_LOCAL`, e.g. by a Python extension
+        let mut info: libc::Dl_info = std::mem::zeroed();
+        if libc::dladdr(curl_easy_init, &mut info) == 0 {
+            return None;
+        }
+        let mut object = libc::dlopen(info.dli_fname, libc::RTLD_LAZY | libc::RTLD_NOLOAD);
+        if object.is_null() {
+            // libcurl is linked into the executable
+            object = libc::dlopen(std::ptr::null(), libc::RTLD_LAZY);
+        }
+        if object.is_null() {
+            return None;
+        }
+        let symbol = libc::dlsym(object, NAME.as_ptr() as *const c_char);
+        libc::dlclose(object);
+
+        // A second libcurl in the process may have one, but it can't work on
+        // handles from ours
+        if symbol.is_null() || !same_object(symbol, curl_easy_init) {
+            return None;
+        }
+        Some(std::mem::transmute::<*mut c_void, curl_easy_impersonate_fn>(symbol))
+    }
+}
+
+#[cfg(not(unix))]
+fn lookup() -> Option<curl_easy_impersonate_fn> {
+    None
+}
+
+/// Whether two addresses are in the same loaded object
+#[cfg(unix)]
+unsafe fn same_object(a: *const std::os::raw::c_void, b: *const std::os::raw::c_void) -> bool {
+    let mut info_a: libc::Dl_info = std::mem::zeroed();
+    let mut info_b: libc::Dl_info = std::mem::zeroed();
+    libc::dladdr(a, &mut info_a) != 0
+        && libc::dladdr(b, &mut info_b) != 0
+        && info_a.dli_fbase == info_b.dli_fbase
+}
@@ -30,17 +30,63 @@
 //!     .unwrap();
 //! ```
 
+pub mod abort;
+pub mod batch;
+mod body;
+pub mod capabilities;
+#[cfg(all(feature = "async-tokio", unix))]
+pub mod client;
+pub mod cookie;
 pub mod curl;
 pub mod error;
+mod ffi;
+pub mod header;
+mod impersonate;
+pub mod multi;
+pub mod multipart;
+pub mod pool;
+pub mod redirect;
+pub mod response;
+pub mod session;
+pub mod share;
+pub mod sse;
+pub mod stream;
+mod transfer;
 pub mod types;
+mod url;
+pub mod websocket;
 
 #[cfg(feature = "python")]
 pub mod python;
 
+use body::{BodySource, RequestBody};
+use std::io::{Read, Seek};
+use std::time::Duration;
+
 // Re-exports
+pub use abort::AbortHandle;
+pub use batch::{batch, batch_iter, Batch, BatchOptions};
+pub use capabilities::{capabilities, Capabilities};
+#[cfg(all(feature = "async-tokio", unix))]
+pub use client::Client;
+pub use cookie::{Cookie, CookieJar, SameSite};
 pub use curl::Curl;
 pub use error::{CurlError, Result};
-pub use types::{Browser, CurlInfo, CurlOpt, HttpVersion};
+pub use header::HeaderMap;
+pub use multi::{Completed, Multi, TransferId};
+pub use multipart::{Multipart, Part};
+pub use pool::{CurlPool, CurlPoolBuilder, PooledCurl};
+pub use redirect::{PostRedirect, RedirectPolicy};
+pub use response::{Hop, Response, Timings};
+pub use session::Session;
+pub use share::{Share, ShareData};
+pub use sse::EventSource;
+pub use stream::ResponseStream;
+pub use types::{
+    Browser, CloseCode, CurlInfo, CurlOpt, Http2Fingerprint, Http2Priority, HttpVersion, Method,
+    TlsVersion, WsFlags,
+};
+pub use websocket::WebSocket;
 
 // Version info
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -73,68 +119,506 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///     .proxies("socks5://localhost:1080")
 ///     .send()
 ///     .unwrap();
+///
+/// // Custom method
+/// let response = Request::new("PURGE", "https://cache.example.com/page")
+///     .send()
+///     .unwrap();
 /// ```
 pub struct Request {
+    method: Method,
     url: String,
-    data: Option<String>,
+    query: Vec<(String, String)>,
+    headers: HeaderMap,
+    body: Option<RequestBody>,
+    /// Content-Type implied by the body, unless the caller sets one
+    content_type: Option<&'static str>,
+    /// Error from a builder method, reported by `send`
+    error: Option<CurlError>,
     impersonate: Option<Browser>,
+    strict_impersonation: Option<bool>,
     proxies: Option<String>,
+    redirect_policy: Option<RedirectPolicy>,
+    post_redirect: Option<PostRedirect>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    abort: Option<AbortHandle>,
 }
 
 impl Request {
-    /// Create a GET request
-    pub fn get(url: impl Into<String>) -> Self {
+    /// Create a request with any method, including custom verbs like `PURGE`
+    pub fn new(method: impl Into<Method>, url: impl Into<String>) -> Self {
         Self {
+            method: method.into(),
             url: url.into(),
-            data: None,
+            query: Vec::new(),
+            headers: HeaderMap::new(),
+            body: None,
+            content_type: None,
+            error: None,
             impersonate: None,
+            strict_impersonation: None,
             proxies: None,
+            redirect_policy: None,
+            post_redirect: None,
+            timeout: None,
+            connect_timeout: None,
+            abort: None,
         }
     }
 
+    /// Create a GET request
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::Get, url)
+    }
+
     /// Create a POST request
-    pub fn post(url: impl Into<String>, data: impl Into<String>) -> Self {
-        Self {
-            url: url.into(),
-            data: Some(data.into()),
-            impersonate: None,
-            proxies: None,
+    pub fn post(url: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self::new(Method::Post, url).body(data)
+    }
+
+    /// Create a PUT request
+    pub fn put(url: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self::new(Method::Put, url).body(data)
+    }
+
+    /// Create a PATCH request
+    pub fn patch(url: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self::new(Method::Patch, url).body(data)
+    }
+
+    /// Create a DELETE request
+    pub fn delete(url: impl Into<String>) -> Self {
+        Self::new(Method::Delete, url)
+    }
+
+    /// Create a HEAD request (the response has no body)
+    pub fn head(url: impl Into<String>) -> Self {
+        Self::new(Method::Head, url)
+    }
+
+    /// Create an OPTIONS request
+    pub fn options(url: impl Into<String>) -> Self {
+        Self::new(Method::Options, url)
+    }
+
+    /// Add query parameters to the URL
+    ///
+    /// Keys and values are percent-encoded and appended to any query string
+    /// already in the URL; the fragment is kept. Can be called repeatedly.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// // Requests https://httpbin.org/get?lang=en&q=rust%20curl&page=2
+    /// let response = Request::get("https://httpbin.org/get?lang=en")
+    ///     .query(&[("q", "rust curl"), ("page", "2")])
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn query<K, V>(mut self, pairs: &[(K, V)]) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.query.extend(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string())),
+        );
+        self
+    }
+
+    /// Add a request header
+    ///
+    /// Repeated names are all sent. Headers set here replace the ones that
+    /// [`impersonate`](Self::impersonate) would send under the same name. An
+    /// invalid name or value is reported by [`send`](Self::send).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let response = Request::get("https://httpbin.org/headers")
+    ///     .header("Authorization", "Bearer token")
+    ///     .header("X-Trace", "a")
+    ///     .header("X-Trace", "b")
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        if let Err(e) = self.headers.append(name, value) {
+            self.error.get_or_insert(e);
+        }
+        self
+    }
+
+    /// Add every header from a map, see [`header`](Self::header)
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        for (name, value) in headers {
+            self.headers.append_unchecked(name, value);
+        }
+        self
+    }
+
+    /// Set a header unless the caller already set it
+    fn default_header(&mut self, name: &str, value: &str) {
+        if !self.headers.contains_key(name) {
+            self.headers
+                .append_unchecked(name.to_string(), value.to_string());
         }
     }
 
+    /// Send pairs as an `application/x-www-form-urlencoded` body
+    ///
+    /// Keys and values are percent-encoded and the Content-Type header is set
+    /// unless one was given with [`header`](Self::header). Replaces any body
+    /// set earlier.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let response = Request::new("POST", "https://httpbin.org/post")
+    ///     .form(&[("user", "jane doe"), ("note", "a&b=c")])
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn form<K, V>(mut self, pairs: &[(K, V)]) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.body = Some(RequestBody::Form(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+                .collect(),
+        ));
+        self.content_type = Some("application/x-www-form-urlencoded");
+        self
+    }
+
+    /// Send a value serialized as JSON
+    ///
+    /// Sets `Content-Type: application/json` unless one was given with
+    /// [`header`](Self::header). A serialization error is returned by
+    /// [`send`](Self::send). Replaces any body set earlier.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    /// use serde_json::json;
+    ///
+    /// let response = Request::new("POST", "https://httpbin.org/post")
+    ///     .json(&json!({"name": "hyprcurl", "tags": ["http", "curl"]}))
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn json<T: serde::Serialize + ?Sized>(mut self, value: &T) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => {
+                self.body = Some(RequestBody::Bytes(body));
+                self.content_type = Some("application/json");
+            }
+            Err(e) => {
+                self.error.get_or_insert(CurlError::JsonSerialize(e));
+            }
+        }
+        self
+    }
+
+    /// Set the request body
+    ///
+    /// Accepts `Vec<u8>`, `&[u8]`, `&str`, `String` or `bytes::Bytes`. The
+    /// data is sent byte-for-byte, NUL bytes included.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let payload: Vec<u8> = vec![0x1f, 0x8b, 0x08, 0x00];
+    /// let response = Request::put("https://httpbin.org/put", payload)
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(RequestBody::Bytes(body.into()));
+        self.content_type = None;
+        self
+    }
+
+    /// Stream the request body from a reader instead of holding it in memory
+    ///
+    /// Pass the length when known to send a `Content-Length`; otherwise the
+    /// body is sent with chunked transfer encoding. See
+    /// [`Curl::set_body_reader`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let file = std::fs::File::open("logs.tar.gz").unwrap();
+    /// let len = file.metadata().unwrap().len();
+    /// let response = Request::new("PUT", "https://httpbin.org/put")
+    ///     .body_reader(file, Some(len))
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn body_reader<R>(mut self, reader: R, len: Option<u64>) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        self.body = Some(RequestBody::Stream(
+            BodySource::Plain(Box::new(reader)),
+            len,
+        ));
+        self.content_type = None;
+        self
+    }
+
+    /// Stream the request body from a reader that can be rewound on redirects
+    /// and auth retries. See [`Curl::set_body_seekable`].
+    pub fn body_seekable<R>(mut self, reader: R, len: Option<u64>) -> Self
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        self.body = Some(RequestBody::Stream(
+            BodySource::Seekable(Box::new(reader)),
+            len,
+        ));
+        self.content_type = None;
+        self
+    }
+
+    /// Send a multipart/form-data body (equivalent to Python's `multipart=`)
+    ///
+    /// Replaces any body set earlier.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{Multipart, Request};
+    ///
+    /// let form = Multipart::new()
+    ///     .text("description", "quarterly report")
+    ///     .file("report", "report.pdf");
+    /// let response = Request::new("POST", "https://httpbin.org/post")
+    ///     .multipart(form)
+    ///     .send()
+    ///     .unwrap();
+    /// ```
+    pub fn multipart(mut self, form: Multipart) -> Self {
+        self.body = Some(RequestBody::Multipart(form));
+        self.content_type = None;
+        self
+    }
+
     /// Set browser impersonation (equivalent to Python's `impersonate="chrome"`)
     pub fn impersonate(mut self, browser: Browser) -> Self {
         self.impersonate = Some(browser);
         self
     }
 
+    /// Fail instead of impersonating only part of the browser's fingerprint
+    ///
+    /// See [`Curl::set_browser_impersonation_strict`].
+    pub fn strict_impersonation(mut self, strict: bool) -> Self {
+        self.strict_impersonation = Some(strict);
+        self
+    }
+
     /// Set proxy (equivalent to Python's `proxies={"https": "..."}`)
     pub fn proxies(mut self, proxy: impl Into<String>) -> Self {
         self.proxies = Some(proxy.into());
         self
     }
 
-    /// Execute the request and return response body
-    pub fn send(self) -> Result<Vec<u8>> {
+    /// Choose how redirects are followed
+    ///
+    /// Defaults to following up to 30 redirects (10 with
+    /// [`impersonate`](Self::impersonate)). See [`RedirectPolicy`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{RedirectPolicy, Request};
+    ///
+    /// let response = Request::get("https://httpbin.org/redirect/1")
+    ///     .redirect_policy(RedirectPolicy::None)
+    ///     .send()
+    ///     .unwrap();
+    /// assert_eq!(response.status(), 302);
+    /// ```
+    pub fn redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = Some(policy);
+        self
+    }
+
+    /// Choose which redirects keep a POST a POST, see [`PostRedirect`]
+    pub fn post_redirect(mut self, post_redirect: PostRedirect) -> Self {
+        self.post_redirect = Some(post_redirect);
+        self
+    }
+
+    /// Abort the request if it takes longer than `timeout` in total
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Give up connecting (including the TLS handshake) after `timeout`
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Handle that aborts this request from another thread
+    ///
+    /// Created on first use; later calls return the same handle.
+    pub fn abort_handle(&mut self) -> AbortHandle {
+        self.abort.get_or_insert_with(AbortHandle::new).clone()
+    }
+
+    /// Let `abort` stop this request, e.g. to abort several requests at once
+    pub fn with_abort_handle(mut self, abort: AbortHandle) -> Self {
+        self.abort = Some(abort);
+        self
+    }
+
+    /// Execute the request and return the response
+    pub fn send(self) -> Result<Response> {
+        self.into_curl()?.perform_response()
+    }
+
+    /// Execute the request on a [`Session`], with its cookies, connections
+    /// and defaults
+    ///
+    /// Anything set on the request overrides the session's defaults.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{Request, Session};
+    ///
+    /// let session = Session::new().unwrap();
+    /// let response = Request::get("https://httpbin.org/headers")
+    ///     .header("X-Trace", "1")
+    ///     .send_with(&session)
+    ///     .unwrap();
+    /// ```
+    pub fn send_with(self, session: &Session) -> Result<Response> {
+        session.send(self)
+    }
+
+    /// Execute the request and stream the response body as it arrives
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let stream = Request::get("https://httpbin.org/stream/20").send_stream().unwrap();
+    /// for chunk in stream {
+    ///     println!("{} bytes", chunk.unwrap().len());
+    /// }
+    /// ```
+    pub fn send_stream(self) -> Result<ResponseStream> {
+        self.into_curl()?.perform_stream()
+    }
+
+    /// Read this request's response as a server-sent events stream,
+    /// reconnecting when the connection drops
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{Browser, Request};
+    ///
+    /// let events = Request::get("https://example.com/updates")
+    ///     .impersonate(Browser::ChromeLatest)
+    ///     .event_source()
+    ///     .unwrap();
+    /// for event in events {
+    ///     println!("{}", event.unwrap().data);
+    /// }
+    /// ```
+    pub fn event_source(self) -> Result<EventSource> {
+        EventSource::with_curl(self.into_curl()?)
+    }
+
+    /// Open a WebSocket connection to this request's `ws://` or `wss://`
+    /// URL
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{Browser, Request};
+    ///
+    /// let mut ws = Request::get("wss://example.com/socket")
+    ///     .impersonate(Browser::ChromeLatest)
+    ///     .header("Origin", "https://example.com")
+    ///     .websocket()
+    ///     .unwrap();
+    /// ws.send_text("hello").unwrap();
+    /// ```
+    pub fn websocket(self) -> Result<WebSocket> {
+        WebSocket::with_curl(self.into_curl()?)
+    }
+
+    /// Build a handle configured for this request
+    pub(crate) fn into_curl(self) -> Result<Curl> {
         let mut curl = Curl::new()?;
+        self.configure(&mut curl)?;
+        Ok(curl)
+    }
+
+    /// Apply this request to a handle in its default state
+    pub(crate) fn configure(mut self, curl: &mut Curl) -> Result<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if let Some(content_type) = self.content_type {
+            self.default_header("Content-Type", content_type);
+        }
 
         if let Some(browser) = self.impersonate {
-            curl.set_browser_impersonation(browser)?;
+            if self.strict_impersonation.unwrap_or(false) {
+                curl.set_browser_impersonation_strict(browser)?;
+            } else {
+                curl.set_browser_impersonation(browser)?;
+            }
         }
 
         if let Some(proxy) = &self.proxies {
             curl.set_proxy(proxy)?;
         }
 
-        curl.set_url(&self.url)?;
-
-        if let Some(data) = &self.data {
-            curl.set_post_data(data)?;
+        if let Some(policy) = self.redirect_policy {
+            curl.set_redirect_policy(policy)?;
+        }
+        if let Some(post_redirect) = self.post_redirect {
+            curl.set_post_redirect(post_redirect)?;
+        }
+        if let Some(timeout) = self.timeout {
+            curl.set_timeout(timeout)?;
+        }
+        if let Some(timeout) = self.connect_timeout {
+            curl.set_connect_timeout(timeout)?;
+        }
+        if let Some(abort) = self.abort {
+            curl.set_abort_handle(abort)?;
         }
 
-        let mut buffer = Vec::new();
-        curl.perform(&mut buffer)?;
-        Ok(buffer)
+        curl.set_url(&url::append_query(&self.url, &self.query)?)?;
+
+        // Caller headers win over the impersonation defaults
+        curl.headers_mut().merge(self.headers);
+
+        match self.body {
+            Some(RequestBody::Bytes(body)) => curl.set_body(body)?,
+            Some(RequestBody::Form(pairs)) => curl.set_body(url::encode_pairs(&pairs)?)?,
+            Some(RequestBody::Stream(source, len)) => curl.set_body_source(source, len)?,
+            Some(RequestBody::Multipart(form)) => curl.set_multipart(form)?,
+            None => {}
+        }
+        curl.set_method(&self.method)
     }
 }
 
@@ -147,9 +631,9 @@ impl Request {
 /// use hyprcurl::get;
 ///
 /// let response = get("https://httpbin.org/get").unwrap();
-/// println!("{}", String::from_utf8_lossy(&response));
+/// println!("{}", response.text().unwrap());
 /// ```
-pub fn get(url: &str) -> Result<Vec<u8>> {
+pub fn get(url: &str) -> Result<Response> {
     Request::get(url).send()
 }
 
@@ -164,7 +648,7 @@ pub fn get(url: &str) -> Result<Vec<u8>> {
 /// let data = r#"{"key": "value"}"#;
 /// let response = post("https://httpbin.org/post", data).unwrap();
 /// ```
-pub fn post(url: &str, data: &str) -> Result<Vec<u8>> {
+pub fn post(url: &str, data: &str) -> Result<Response> {
     Request::post(url, data).send()
 }
 
@@ -0,0 +1,287 @@
+//! Many transfers on one thread through libcurl's multi interface
+
+use crate::curl::Curl;
+use crate::error::{check_multi_code, CurlError, Result};
+use crate::response::Response;
+use crate::transfer::Transfer;
+use crate::Request;
+use std::collections::{HashMap, VecDeque};
+use std::os::raw::{c_int, c_long};
+use std::ptr;
+use std::time::Duration;
+
+/// Longest single wait in [`Multi::run`]; libcurl returns earlier when a
+/// transfer needs attention
+pub(crate) const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Identifies a transfer added to a [`Multi`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TransferId(u64);
+
+/// A transfer that has finished, successfully or not
+pub struct Completed {
+    /// Returned by [`Multi::add`] for this transfer
+    pub id: TransferId,
+    /// The response, or why the transfer failed
+    pub result: Result<Response>,
+    /// The handle, ready to be set up for the next request
+    pub curl: Curl,
+}
+
+/// Runs any number of transfers at once on the calling thread
+///
+/// Handles are moved in with [`add`](Self::add) and come back in a
+/// [`Completed`] once their transfer is done. [`run`](Self::run) waits until
+/// everything has finished; [`perform`](Self::perform),
+/// [`poll`](Self::poll) and [`next_completed`](Self::next_completed) let the
+/// caller add more transfers or pick up results along the way.
+///
+/// Redirects, the redirect policy and cookie jars work as with
+/// [`Curl::perform_response`].
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, Multi, Request};
+///
+/// let mut multi = Multi::new().unwrap();
+/// for i in 0..10 {
+///     let request = Request::get(format!("https://example.com/item/{}", i))
+///         .impersonate(Browser::ChromeLatest);
+///     multi.add_request(request).unwrap();
+/// }
+/// for completed in multi.run().unwrap() {
+///     match completed.result {
+///         Ok(response) => println!("{:?}: {}", completed.id, response.status()),
+///         Err(e) => println!("{:?} failed: {}", completed.id, e),
+///     }
+/// }
+/// ```
+pub struct Multi {
+    handle: *mut curl_sys::CURLM,
+    /// Transfers in the multi handle, keyed by easy handle
+    transfers: HashMap<usize, (TransferId, Transfer)>,
+    completed: VecDeque<Completed>,
+    next_id: u64,
+}
+
+// The multi handle and its easy handles are only used through `&mut self`
+unsafe impl Send for Multi {}
+
+impl Multi {
+    /// Create an empty multi handle
+    pub fn new() -> Result<Self> {
+        crate::init_curl();
+        let handle = unsafe { curl_sys::curl_multi_init() };
+        if handle.is_null() {
+            return Err(CurlError::InitError);
+        }
+        Ok(Multi {
+            handle,
+            transfers: HashMap::new(),
+            completed: VecDeque::new(),
+            next_id: 0,
+        })
+    }
+
+    /// Open at most this many connections at once (0 for no limit)
+    ///
+    /// Transfers beyond the limit wait for a connection to become free.
+    pub fn set_max_total_connections(&mut self, max: usize) -> Result<()> {
+        self.setopt_long(curl_sys::CURLMOPT_MAX_TOTAL_CONNECTIONS, max)
+    }
+
+    /// Open at most this many connections to any one host (0 for no limit)
+    pub fn set_max_host_connections(&mut self, max: usize) -> Result<()> {
+        self.setopt_long(curl_sys::CURLMOPT_MAX_HOST_CONNECTIONS, max)
+    }
+
+    fn setopt_long(&mut self, option: curl_sys::CURLMoption, value: usize) -> Result<()> {
+        let value = c_long::try_from(value)
+            .map_err(|_| CurlError::InvalidOption(format!("{} is out of range", value)))?;
+        check_multi_code(unsafe { curl_sys::curl_multi_setopt(self.handle, option, value) })
+    }
+
+    /// Start a transfer with a configured handle
+    pub fn add(&mut self, curl: Curl) -> Result<TransferId> {
+        let transfer = Transfer::new(curl)?;
+        let easy = transfer.raw_handle();
+        check_multi_code(unsafe { curl_sys::curl_multi_add_handle(self.handle, easy) })?;
+
+        let id = TransferId(self.next_id);
+        self.next_id += 1;
+        self.transfers.insert(easy as usize, (id, transfer));
+        Ok(id)
+    }
+
+    /// Start a transfer for a request
+    pub fn add_request(&mut self, request: Request) -> Result<TransferId> {
+        self.add(request.into_curl()?)
+    }
+
+    /// Abort a transfer that hasn't completed yet and get its handle back
+    ///
+    /// Returns `None` if the transfer already completed or was removed.
+    pub fn remove(&mut self, id: TransferId) -> Result<Option<Curl>> {
+        let Some(&easy) = self
+            .transfers
+            .iter()
+            .find_map(|(easy, (transfer_id, _))| (*transfer_id == id).then_some(easy))
+        else {
+            return Ok(None);
+        };
+        check_multi_code(unsafe {
+            curl_sys::curl_multi_remove_handle(self.handle, easy as *mut curl_sys::CURL)
+        })?;
+        let (_, transfer) = self
+            .transfers
+            .remove(&easy)
+            .expect("transfer was just found");
+        Ok(Some(transfer.into_curl()))
+    }
+
+    /// Number of transfers that haven't completed yet
+    pub fn len(&self) -> usize {
+        self.transfers.len()
+    }
+
+    /// Whether every transfer has completed
+    pub fn is_empty(&self) -> bool {
+        self.transfers.is_empty()
+    }
+
+    /// Move all transfers forward as far as possible without waiting
+    ///
+    /// Returns the number of transfers still running. Transfers that
+    /// finished are queued for [`next_completed`](Self::next_completed).
+    pub fn perform(&mut self) -> Result<usize> {
+        let mut running: c_int = 0;
+        check_multi_code(unsafe { curl_sys::curl_multi_perform(self.handle, &mut running) })?;
+        self.read_messages();
+        Ok(self.transfers.len())
+    }
+
+    /// Wait up to `timeout` for activity on any transfer
+    ///
+    /// Returns early when a transfer needs attention; call
+    /// [`perform`](Self::perform) afterwards.
+    pub fn poll(&mut self, timeout: Duration) -> Result<()> {
+        let timeout_ms = c_int::try_from(timeout.as_millis()).unwrap_or(c_int::MAX);
+        check_multi_code(unsafe {
+            curl_sys::curl_multi_poll(self.handle, ptr::null_mut(), 0, timeout_ms, ptr::null_mut())
+        })
+    }
+
+    /// Take the next finished transfer, in the order they finished
+    pub fn next_completed(&mut self) -> Option<Completed> {
+        self.completed.pop_front()
+    }
+
+    /// Run until every transfer has completed, and return them all in the
+    /// order they finished
+    pub fn run(&mut self) -> Result<Vec<Completed>> {
+        while self.perform()? > 0 {
+            self.poll(POLL_TIMEOUT)?;
+        }
+        Ok(self.completed.drain(..).collect())
+    }
+
+    /// Get raw multi handle (for advanced use)
+    pub fn raw_handle(&self) -> *mut curl_sys::CURLM {
+        self.handle
+    }
+
+    /// Queue the transfers libcurl reports as done
+    fn read_messages(&mut self) {
+        loop {
+            let mut queued: c_int = 0;
+            let msg = unsafe { curl_sys::curl_multi_info_read(self.handle, &mut queued) };
+            if msg.is_null() {
+                break;
+            }
+            let msg = unsafe { &*msg };
+            if msg.msg != curl_sys::CURLMSG_DONE {
+                continue;
+            }
+            let easy = msg.easy_handle;
+            // `data` is a union whose `result` member holds the CURLcode
+            let code = msg.data as usize as curl_sys::CURLcode;
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.handle, easy);
+            }
+
+            let Some((_, transfer)) = self.transfers.get_mut(&(easy as usize)) else {
+                continue;
+            };
+            let outcome = match transfer.complete(code) {
+                // A redirect followed by hand goes on as a new transfer
+                Ok(true) => {
+                    match check_multi_code(unsafe {
+                        curl_sys::curl_multi_add_handle(self.handle, easy)
+                    }) {
+                        Ok(()) => continue,
+                        Err(e) => Err(e),
+                    }
+                }
+                Ok(false) => Ok(()),
+                Err(e) => Err(e),
+            };
+
+            let (id, mut transfer) = self
+                .transfers
+                .remove(&(easy as usize))
+                .expect("transfer was just found");
+            let result = outcome.and_then(|()| transfer.response());
+            self.completed.push_back(Completed {
+                id,
+                result,
+                curl: transfer.into_curl(),
+            });
+        }
+    }
+}
+
+impl Drop for Multi {
+    fn drop(&mut self) {
+        for easy in self.transfers.keys() {
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.handle, *easy as *mut curl_sys::CURL);
+            }
+        }
+        // The transfers' handles are cleaned up after being removed
+        self.transfers.clear();
+        unsafe {
+            curl_sys::curl_multi_cleanup(self.handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_transfers_complete() {
+        let mut multi = Multi::new().unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let mut curl = Curl::new().unwrap();
+            curl.set_url("http://127.0.0.1:1/").unwrap();
+            ids.push(multi.add(curl).unwrap());
+        }
+        let removed = multi.remove(ids[1]).unwrap();
+        assert!(removed.is_some());
+        assert!(multi.remove(ids[1]).unwrap().is_none());
+
+        let completed = multi.run().unwrap();
+        assert!(multi.is_empty());
+        let mut finished: Vec<_> = completed.iter().map(|c| c.id).collect();
+        finished.sort();
+        assert_eq!(finished, vec![ids[0], ids[2]]);
+        for completed in completed {
+            assert!(matches!(
+                completed.result,
+                Err(CurlError::CurlCode { code, .. }) if code == curl_sys::CURLE_COULDNT_CONNECT
+            ));
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! multipart/form-data bodies built on the libcurl MIME API
+
+use crate::body::{read_func, seek_func, BodyReader, BodySource};
+use crate::error::{check_code, CurlError, Result};
+use crate::ffi;
+use std::ffi::CString;
+use std::io::{self, Read, Seek};
+use std::os::raw::{c_char, c_void};
+use std::path::PathBuf;
+
+/// Builder for a multipart/form-data request body
+///
+/// Equivalent to curl_cffi's `multipart=` argument. Parts are sent in the
+/// order they were added.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Multipart, Part, Request};
+///
+/// let form = Multipart::new()
+///     .text("title", "Holiday")
+///     .file("photo", "beach.jpg")
+///     .part(
+///         Part::bytes("thumbnail", vec![0x89, 0x50, 0x4e, 0x47])
+///             .file_name("thumb.png")
+///             .content_type("image/png"),
+///     );
+///
+/// let response = Request::new("POST", "https://httpbin.org/post")
+///     .multipart(form)
+///     .send()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct Multipart {
+    parts: Vec<Part>,
+}
+
+impl Multipart {
+    /// Create an empty form
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a text field
+    pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.part(Part::text(name, value))
+    }
+
+    /// Add a field holding in-memory bytes
+    pub fn bytes(self, name: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        self.part(Part::bytes(name, data))
+    }
+
+    /// Add a file read from `path` when the request is sent
+    pub fn file(self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.part(Part::file(name, path))
+    }
+
+    /// Add a field streamed from a reader while the request is sent
+    pub fn reader<R>(self, name: impl Into<String>, reader: R, len: Option<u64>) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        self.part(Part::reader(name, reader, len))
+    }
+
+    /// Add a fully configured part
+    pub fn part(mut self, part: Part) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// Number of parts in the form
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Whether the form has no parts
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+}
+
+/// Contents of a single form part
+enum PartData {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+    Stream(BodySource, Option<u64>),
+}
+
+/// A single part of a [`Multipart`] form
+///
+/// Use the constructors for the contents and [`file_name`](Self::file_name)
+/// and [`content_type`](Self::content_type) to set the part headers.
+pub struct Part {
+    name: String,
+    data: PartData,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+impl Part {
+    fn new(name: impl Into<String>, data: PartData) -> Self {
+        Part {
+            name: name.into(),
+            data,
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// A text field
+    pub fn text(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::new(name, PartData::Bytes(value.into().into_bytes()))
+    }
+
+    /// A field holding in-memory bytes, NUL bytes included
+    pub fn bytes(name: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self::new(name, PartData::Bytes(data.into()))
+    }
+
+    /// A file read from `path` while the request is sent
+    ///
+    /// The file name defaults to the last component of the path.
+    pub fn file(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self::new(name, PartData::File(path.into()))
+    }
+
+    /// A field streamed from a reader
+    ///
+    /// With an unknown `len` the whole request is sent with chunked transfer
+    /// encoding. The reader can't be rewound; see
+    /// [`reader_seekable`](Self::reader_seekable).
+    pub fn reader<R>(name: impl Into<String>, reader: R, len: Option<u64>) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        Self::new(
+            name,
+            PartData::Stream(BodySource::Plain(Box::new(reader)), len),
+        )
+    }
+
+    /// A field streamed from a reader that can be rewound on redirects
+    pub fn reader_seekable<R>(name: impl Into<String>, reader: R, len: Option<u64>) -> Self
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        Self::new(
+            name,
+            PartData::Stream(BodySource::Seekable(Box::new(reader)), len),
+        )
+    }
+
+    /// Set the `filename` of the part's Content-Disposition
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Set the part's Content-Type
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+}
+
+fn to_cstring(what: &str, value: &str) -> Result<CString> {
+    CString::new(value)
+        .map_err(|_| CurlError::InvalidOption(format!("Invalid {}: {}", what, value)))
+}
+
+/// A `curl_mime` structure built from a [`Multipart`] form
+///
+/// Owned by the `Curl` handle that sends it. It must outlive any use by that
+/// handle, so it is only dropped once `CURLOPT_MIMEPOST` no longer points at it.
+pub(crate) struct Mime {
+    raw: *mut ffi::curl_mime,
+    // Streamed parts, boxed so the callback pointers stay valid as the Vec grows
+    #[allow(clippy::vec_box)]
+    readers: Vec<Box<BodyReader>>,
+}
+
+impl Mime {
+    pub(crate) fn new(handle: *mut curl_sys::CURL, form: Multipart) -> Result<Self> {
+        let raw = unsafe { ffi::curl_mime_init(handle) };
+        if raw.is_null() {
+            return Err(CurlError::InitError);
+        }
+
+        // Freed by Drop if adding a part fails
+        let mut mime = Mime {
+            raw,
+            readers: Vec::new(),
+        };
+        for part in form.parts {
+            mime.add_part(part)?;
+        }
+        Ok(mime)
+    }
+
+    fn add_part(&mut self, part: Part) -> Result<()> {
+        let raw_part = unsafe { ffi::curl_mime_addpart(self.raw) };
+        if raw_part.is_null() {
+            return Err(CurlError::Other("Failed to add multipart part".to_string()));
+        }
+
+        let name = to_cstring("part name", &part.name)?;
+        check_code(unsafe { ffi::curl_mime_name(raw_part, name.as_ptr()) })?;
+
+        match part.data {
+            PartData::Bytes(data) => {
+                // libcurl copies the data
+                check_code(unsafe {
+                    ffi::curl_mime_data(raw_part, data.as_ptr() as *const c_char, data.len())
+                })?;
+            }
+            PartData::File(path) => {
+                let path_str = path.to_str().ok_or_else(|| {
+                    CurlError::InvalidOption(format!("Invalid file path: {}", path.display()))
+                })?;
+                let c_path = to_cstring("file path", path_str)?;
+                // Report a missing file with the OS error rather than CURLE_READ_ERROR
+                std::fs::metadata(&path)?;
+                check_code(unsafe { ffi::curl_mime_filedata(raw_part, c_path.as_ptr()) })?;
+            }
+            PartData::Stream(source, len) => {
+                let mut body = Box::new(BodyReader::new(source));
+                let userdata = &mut *body as *mut BodyReader as *mut c_void;
+                check_code(unsafe {
+                    ffi::curl_mime_data_cb(
+                        raw_part,
+                        // -1 tells libcurl the size is unknown
+                        len.map_or(-1, |len| len as curl_sys::curl_off_t),
+                        read_func,
+                        seek_func,
+                        None,
+                        userdata,
+                    )
+                })?;
+                self.readers.push(body);
+            }
+        }
+
+        if let Some(file_name) = &part.file_name {
+            let file_name = to_cstring("file name", file_name)?;
+            check_code(unsafe { ffi::curl_mime_filename(raw_part, file_name.as_ptr()) })?;
+        }
+        if let Some(content_type) = &part.content_type {
+            let content_type = to_cstring("content type", content_type)?;
+            check_code(unsafe { ffi::curl_mime_type(raw_part, content_type.as_ptr()) })?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut ffi::curl_mime {
+        self.raw
+    }
+
+    /// First error returned by a streamed part's reader
+    pub(crate) fn take_error(&mut self) -> Option<io::Error> {
+        self.readers.iter_mut().find_map(|r| r.take_error())
+    }
+}
+
+impl Drop for Mime {
+    fn drop(&mut self) {
+        // Frees the parts before the readers their callbacks point at
+        unsafe {
+            ffi::curl_mime_free(self.raw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_rejects_missing_file() {
+        crate::init_curl();
+        let handle = unsafe { curl_sys::curl_easy_init() };
+        let form = Multipart::new()
+            .text("title", "x")
+            .file("upload", "/nonexistent/hyprcurl-upload.bin");
+
+        let result = Mime::new(handle, form);
+        assert!(matches!(result, Err(CurlError::IoError(_))));
+        unsafe { curl_sys::curl_easy_cleanup(handle) };
+    }
+}
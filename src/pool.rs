@@ -0,0 +1,375 @@
+//! Pool of reusable handles for blocking workers on many threads
+
+use crate::curl::Curl;
+use crate::error::Result;
+use crate::share::{Share, ShareData};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+type Configure = dyn Fn(&mut Curl) -> Result<()> + Send + Sync;
+
+struct PoolState {
+    idle: Vec<Curl>,
+    /// Handles that exist, idle or checked out
+    created: usize,
+}
+
+struct PoolInner {
+    state: Mutex<PoolState>,
+    returned: Condvar,
+    max_size: usize,
+    configure: Option<Arc<Configure>>,
+    share: Option<Share>,
+}
+
+impl PoolInner {
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        // The state is only changed by short, non-panicking updates
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Give back a handle's slot, e.g. after setting it up failed
+    fn forget(&self) {
+        self.lock().created -= 1;
+        self.returned.notify_one();
+    }
+}
+
+/// A fixed number of handles shared by blocking workers
+///
+/// [`get`](Self::get) checks out a handle, creating one while fewer than
+/// `max_size` exist and otherwise waiting for one to come back, so callers
+/// slow down instead of opening ever more connections. Handles go back to
+/// the pool when the [`PooledCurl`] is dropped: they are [`reset`] and
+/// their cookies cleared, while connections and TLS sessions are kept for
+/// the next user.
+///
+/// Clones refer to the same pool.
+///
+/// [`reset`]: Curl::reset
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, CurlPool};
+/// use std::thread;
+///
+/// let pool = CurlPool::builder(4)
+///     .configure(|curl| curl.set_browser_impersonation(Browser::ChromeLatest))
+///     .build();
+///
+/// let workers: Vec<_> = (0..16)
+///     .map(|i| {
+///         let pool = pool.clone();
+///         thread::spawn(move || {
+///             let mut curl = pool.get().unwrap();
+///             curl.set_url(&format!("https://example.com/page/{}", i)).unwrap();
+///             curl.perform_response().unwrap().status()
+///         })
+///     })
+///     .collect();
+/// for worker in workers {
+///     println!("{}", worker.join().unwrap());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct CurlPool {
+    inner: Arc<PoolInner>,
+}
+
+impl CurlPool {
+    /// Create a pool of at most `max_size` handles, created as needed
+    ///
+    /// # Panics
+    /// Panics if `max_size` is zero.
+    pub fn new(max_size: usize) -> Self {
+        CurlPool::builder(max_size).build()
+    }
+
+    /// Start a pool of at most `max_size` handles whose handles are set up
+    /// before they are checked out
+    ///
+    /// # Panics
+    /// Panics if `max_size` is zero.
+    pub fn builder(max_size: usize) -> CurlPoolBuilder {
+        assert!(
+            max_size > 0,
+            "a CurlPool needs room for at least one handle"
+        );
+        CurlPoolBuilder {
+            max_size,
+            configure: None,
+            share: None,
+        }
+    }
+
+    /// Maximum number of handles
+    pub fn max_size(&self) -> usize {
+        self.inner.max_size
+    }
+
+    /// Number of handles that can be checked out without waiting
+    pub fn available(&self) -> usize {
+        let state = self.inner.lock();
+        state.idle.len() + (self.inner.max_size - state.created)
+    }
+
+    /// Check out a handle, waiting for one to be returned if all are in use
+    pub fn get(&self) -> Result<PooledCurl> {
+        self.checkout(None)
+            .map(|curl| curl.expect("waiting without a deadline returns a handle"))
+    }
+
+    /// Check out a handle if one is free right now
+    pub fn try_get(&self) -> Result<Option<PooledCurl>> {
+        self.checkout(Some(Instant::now()))
+    }
+
+    /// Check out a handle, waiting at most `timeout` for one to be returned
+    pub fn get_timeout(&self, timeout: Duration) -> Result<Option<PooledCurl>> {
+        self.checkout(Some(Instant::now() + timeout))
+    }
+
+    fn checkout(&self, deadline: Option<Instant>) -> Result<Option<PooledCurl>> {
+        let mut state = self.inner.lock();
+        let reused = loop {
+            if let Some(curl) = state.idle.pop() {
+                break Some(curl);
+            }
+            if state.created < self.inner.max_size {
+                state.created += 1;
+                break None;
+            }
+            state = match deadline {
+                None => self
+                    .inner
+                    .returned
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+                    self.inner
+                        .returned
+                        .wait_timeout(state, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        };
+        drop(state);
+
+        // New handles are created outside the lock; a failure frees the slot
+        let set_up = match reused {
+            Some(curl) => self.set_up(curl),
+            None => Curl::new().and_then(|curl| self.set_up(curl)),
+        };
+        match set_up {
+            Ok(curl) => Ok(Some(PooledCurl {
+                curl: Some(curl),
+                pool: self.inner.clone(),
+            })),
+            Err(e) => {
+                self.inner.forget();
+                Err(e)
+            }
+        }
+    }
+
+    fn set_up(&self, mut curl: Curl) -> Result<Curl> {
+        if let Some(share) = &self.inner.share {
+            curl.set_share(share)?;
+        }
+        if let Some(configure) = &self.inner.configure {
+            configure(&mut curl)?;
+        }
+        Ok(curl)
+    }
+}
+
+impl fmt::Debug for CurlPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.lock();
+        f.debug_struct("CurlPool")
+            .field("max_size", &self.inner.max_size)
+            .field("created", &state.created)
+            .field("idle", &state.idle.len())
+            .finish()
+    }
+}
+
+/// Builder for a [`CurlPool`], created with [`CurlPool::builder`]
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, CurlPool, Share};
+///
+/// let pool = CurlPool::builder(8)
+///     .configure(|curl| curl.set_browser_impersonation(Browser::ChromeLatest))
+///     .share(Share::new()?)
+///     .build();
+/// # Ok::<(), hyprcurl::CurlError>(())
+/// ```
+pub struct CurlPoolBuilder {
+    max_size: usize,
+    configure: Option<Arc<Configure>>,
+    share: Option<Share>,
+}
+
+impl CurlPoolBuilder {
+    /// Set up every handle with `configure` before it is checked out
+    ///
+    /// Runs on each checkout, because returning a handle resets its options.
+    pub fn configure<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(&mut Curl) -> Result<()> + Send + Sync + 'static,
+    {
+        self.configure = Some(Arc::new(configure));
+        self
+    }
+
+    /// Attach every handle to `share`
+    ///
+    /// Handles in a pool already keep their own connections between uses;
+    /// a share also lets them use each other's.
+    pub fn share(mut self, share: Share) -> Self {
+        self.share = Some(share);
+        self
+    }
+
+    /// Create the pool; no handle is created until one is checked out
+    pub fn build(self) -> CurlPool {
+        CurlPool {
+            inner: Arc::new(PoolInner {
+                state: Mutex::new(PoolState {
+                    idle: Vec::new(),
+                    created: 0,
+                }),
+                returned: Condvar::new(),
+                max_size: self.max_size,
+                configure: self.configure,
+                share: self.share,
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for CurlPoolBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CurlPoolBuilder")
+            .field("max_size", &self.max_size)
+            .field("configure", &self.configure.is_some())
+            .field("share", &self.share.is_some())
+            .finish()
+    }
+}
+
+/// A handle checked out of a [`CurlPool`]
+///
+/// Derefs to [`Curl`] and goes back to the pool when dropped.
+pub struct PooledCurl {
+    /// Only `None` while being dropped
+    curl: Option<Curl>,
+    pool: Arc<PoolInner>,
+}
+
+impl Deref for PooledCurl {
+    type Target = Curl;
+
+    fn deref(&self) -> &Curl {
+        self.curl.as_ref().expect("handle is present until dropped")
+    }
+}
+
+impl DerefMut for PooledCurl {
+    fn deref_mut(&mut self) -> &mut Curl {
+        self.curl.as_mut().expect("handle is present until dropped")
+    }
+}
+
+impl Drop for PooledCurl {
+    fn drop(&mut self) {
+        let Some(mut curl) = self.curl.take() else {
+            return;
+        };
+
+        curl.reset();
+        let shared_cookies = self
+            .pool
+            .share
+            .as_ref()
+            .is_some_and(|share| share.shares(ShareData::Cookies));
+        // The next user shouldn't be sent this user's cookies
+        if !shared_cookies && curl.clear_cookies().is_err() {
+            // Keeping a handle with someone else's cookies is worse than
+            // opening a new one
+            self.pool.forget();
+            return;
+        }
+
+        self.pool.lock().idle.push(curl);
+        self.pool.returned.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CurlOpt;
+    use crate::{Cookie, CookieJar};
+    use std::thread;
+
+    #[test]
+    fn test_pool_waits_for_free_handle() {
+        let pool = CurlPool::new(2);
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        assert_eq!(pool.available(), 0);
+        assert!(pool.try_get().unwrap().is_none());
+        assert!(pool
+            .get_timeout(Duration::from_millis(20))
+            .unwrap()
+            .is_none());
+
+        let waiter = {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get().map(|_| ()))
+        };
+        thread::sleep(Duration::from_millis(20));
+        drop(first);
+        waiter.join().unwrap().unwrap();
+        drop(second);
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[test]
+    fn test_returned_handle_is_clean() {
+        let pool = CurlPool::builder(1)
+            .configure(|curl| curl.setopt_str(CurlOpt::UserAgent, "pool"))
+            .build();
+        let mut curl = pool.get().unwrap();
+        let jar = CookieJar::new();
+        jar.insert(Cookie::new("sid", "abc", "example.com"))
+            .unwrap();
+        curl.set_cookie_jar(jar).unwrap();
+        curl.prepare_transfer().unwrap();
+        curl.add_header("X-User: first").unwrap();
+        drop(curl);
+
+        let curl = pool.get().unwrap();
+        assert!(curl.headers().is_empty());
+        assert!(curl.cookies().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_setup_frees_slot() {
+        let pool = CurlPool::builder(1)
+            .configure(|curl| curl.set_url("bad\0url"))
+            .build();
+        assert!(pool.get().is_err());
+        assert_eq!(pool.available(), 1);
+    }
+}
@@ -1,11 +1,20 @@
 //! Python bindings using PyO3
 
+use crate::abort::{self, AbortHandle};
+use crate::batch::{batch, BatchOptions, DEFAULT_CONCURRENCY};
 use crate::curl::Curl as RustCurl;
 use crate::error::CurlError;
-use crate::types::{Browser, HttpVersion};
-use pyo3::exceptions::PyException;
+use crate::header::HeaderMap;
+use crate::multipart::{Multipart as RustMultipart, Part};
+use crate::session::Session as RustSession;
+use crate::sse::{Event as RustEvent, EventSource as RustEventSource};
+use crate::types::Browser;
+use crate::Request;
+use pyo3::exceptions::{PyException, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict};
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
 
 /// Convert CurlError to PyErr
 impl From<CurlError> for PyErr {
@@ -14,6 +23,142 @@ impl From<CurlError> for PyErr {
     }
 }
 
+/// How often a running transfer lets Python handle signals
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+thread_local! {
+    /// Exception raised by a signal handler while a transfer was running
+    static SIGNAL_ERROR: RefCell<Option<PyErr>> = const { RefCell::new(None) };
+    static LAST_SIGNAL_CHECK: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Run Python's signal handlers from within a transfer; an exception they
+/// raise (KeyboardInterrupt for Ctrl+C) aborts the transfer
+fn signal_raised() -> bool {
+    let now = Instant::now();
+    let recent = LAST_SIGNAL_CHECK.with(|last| {
+        last.replace(Some(now))
+            .is_some_and(|checked| now - checked < SIGNAL_CHECK_INTERVAL)
+    });
+    if recent {
+        return false;
+    }
+    Python::with_gil(|py| match py.check_signals() {
+        Ok(()) => false,
+        Err(err) => {
+            SIGNAL_ERROR.with(|slot| *slot.borrow_mut() = Some(err));
+            true
+        }
+    })
+}
+
+/// Run blocking transfers with the GIL released
+///
+/// Transfers with an abort handle are aborted when a signal handler raises,
+/// and its exception is raised instead of the transfer's result.
+fn interruptible<T: Send>(py: Python<'_>, f: impl FnOnce() -> T + Send) -> PyResult<T> {
+    SIGNAL_ERROR.with(|slot| slot.borrow_mut().take());
+    LAST_SIGNAL_CHECK.with(|last| last.set(None));
+    let result = abort::with_interrupt_check(signal_raised, || py.allow_threads(f));
+    match SIGNAL_ERROR.with(|slot| slot.borrow_mut().take()) {
+        Some(err) => Err(err),
+        None => Ok(result),
+    }
+}
+
+/// Request body accepted from Python: `bytes` are sent as-is, `str` as UTF-8
+#[derive(FromPyObject)]
+enum PostData<'py> {
+    Bytes(Bound<'py, PyBytes>),
+    Text(String),
+}
+
+impl PostData<'_> {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            PostData::Bytes(bytes) => bytes.as_bytes(),
+            PostData::Text(text) => text.as_bytes(),
+        }
+    }
+}
+
+/// One part added with `Multipart.addpart`
+#[derive(Clone)]
+struct PartSpec {
+    name: String,
+    content_type: Option<String>,
+    filename: Option<String>,
+    local_path: Option<String>,
+    data: Option<Vec<u8>>,
+}
+
+/// multipart/form-data body (curl_cffi's `CurlMime`)
+///
+/// The form can be sent any number of times; files are read on each send.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct Multipart {
+    parts: Vec<PartSpec>,
+}
+
+#[pymethods]
+impl Multipart {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a part with either in-memory `data` or a file at `local_path`
+    #[pyo3(signature = (name, *, content_type=None, filename=None, local_path=None, data=None))]
+    fn addpart(
+        &mut self,
+        name: String,
+        content_type: Option<String>,
+        filename: Option<String>,
+        local_path: Option<String>,
+        data: Option<PostData<'_>>,
+    ) -> PyResult<()> {
+        if local_path.is_some() == data.is_some() {
+            return Err(PyValueError::new_err(
+                "addpart needs exactly one of local_path or data",
+            ));
+        }
+        self.parts.push(PartSpec {
+            name,
+            content_type,
+            filename,
+            local_path,
+            data: data.map(|d| d.as_bytes().to_vec()),
+        });
+        Ok(())
+    }
+
+    fn __len__(&self) -> usize {
+        self.parts.len()
+    }
+}
+
+impl Multipart {
+    fn to_rust(&self) -> RustMultipart {
+        self.parts
+            .iter()
+            .cloned()
+            .fold(RustMultipart::new(), |form, spec| {
+                let mut part = match (spec.local_path, spec.data) {
+                    (Some(path), _) => Part::file(spec.name, path),
+                    (None, data) => Part::bytes(spec.name, data.unwrap_or_default()),
+                };
+                if let Some(filename) = spec.filename {
+                    part = part.file_name(filename);
+                }
+                if let Some(content_type) = spec.content_type {
+                    part = part.content_type(content_type);
+                }
+                form.part(part)
+            })
+    }
+}
+
 /// Python wrapper for Curl
 #[pyclass]
 pub struct Curl {
@@ -56,7 +201,10 @@ impl Curl {
         Ok(())
     }
 
-    /// Impersonate browser
+    /// Impersonate a browser with libcurl-impersonate, e.g. `"chrome120"`
+    ///
+    /// Raises when the linked libcurl isn't libcurl-impersonate.
+    #[pyo3(signature = (target, default_headers=true))]
     fn impersonate(&mut self, target: &str, default_headers: bool) -> PyResult<()> {
         self.inner.impersonate(target, default_headers)?;
         Ok(())
@@ -68,18 +216,24 @@ impl Curl {
         Ok(())
     }
 
-    /// Set POST data
-    fn set_post_data(&mut self, data: &str) -> PyResult<()> {
-        self.inner.set_post_data(data)?;
+    /// Set POST data (`bytes` or `str`)
+    fn set_post_data(&mut self, data: PostData<'_>) -> PyResult<()> {
+        self.inner.set_post_bytes(data.as_bytes())?;
+        Ok(())
+    }
+
+    /// Send a multipart/form-data body
+    fn set_multipart(&mut self, form: &Multipart) -> PyResult<()> {
+        self.inner.set_multipart(form.to_rust())?;
         Ok(())
     }
 
     /// Perform request
     fn perform<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         let mut buffer = Vec::new();
-
-        // Release GIL during blocking I/O
-        py.allow_threads(|| self.inner.perform(&mut buffer))?;
+        // A fresh handle, as an interrupted transfer aborts its handle for good
+        self.inner.set_abort_handle(AbortHandle::new())?;
+        interruptible(py, || self.inner.perform(&mut buffer))??;
 
         Ok(PyBytes::new_bound(py, &buffer))
     }
@@ -111,6 +265,217 @@ impl Curl {
     }
 }
 
+/// Session reusing one handle across requests (curl_cffi's `requests.Session`)
+///
+/// Cookies set by responses are sent back, connections are kept alive, and
+/// `headers`, `impersonate`, `proxies` and `timeout` apply to every request
+/// unless the request passes its own.
+#[pyclass]
+pub struct Session {
+    inner: RustSession,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    #[pyo3(signature = (headers=None, impersonate=None, proxies=None, timeout=None))]
+    fn new(
+        headers: Option<&Bound<'_, PyDict>>,
+        impersonate: Option<&str>,
+        proxies: Option<&str>,
+        timeout: Option<f64>,
+    ) -> PyResult<Self> {
+        let mut inner = RustSession::new()?.headers(to_header_map(headers)?);
+        if let Some(browser_str) = impersonate {
+            inner = inner.impersonate(parse_browser(browser_str)?);
+        }
+        if let Some(proxy) = proxies {
+            inner = inner.proxies(proxy);
+        }
+        if let Some(secs) = timeout {
+            inner = inner.timeout(to_duration(secs)?);
+        }
+        Ok(Session { inner })
+    }
+
+    /// GET request with the session's cookies and defaults
+    #[pyo3(signature = (url, headers=None, impersonate=None, proxies=None, timeout=None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        headers: Option<&Bound<'_, PyDict>>,
+        impersonate: Option<&str>,
+        proxies: Option<&str>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let request = with_overrides(Request::get(url), headers, impersonate, proxies, timeout)?;
+        self.send(py, request)
+    }
+
+    /// POST request with `data` (`bytes` or `str`) or a `Multipart` form
+    #[pyo3(signature = (url, data=None, multipart=None, headers=None, impersonate=None, proxies=None, timeout=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn post<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        data: Option<PostData<'_>>,
+        multipart: Option<PyRef<'_, Multipart>>,
+        headers: Option<&Bound<'_, PyDict>>,
+        impersonate: Option<&str>,
+        proxies: Option<&str>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let request = match (data, multipart) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "data and multipart can't be used together",
+                ))
+            }
+            (_, Some(form)) => Request::new("POST", url).multipart(form.to_rust()),
+            (data, None) => Request::post(url, data.as_ref().map_or(&[][..], |d| d.as_bytes())),
+        };
+        let request = with_overrides(request, headers, impersonate, proxies, timeout)?;
+        self.send(py, request)
+    }
+}
+
+impl Session {
+    fn send<'py>(&self, py: Python<'py>, request: Request) -> PyResult<Bound<'py, PyBytes>> {
+        let request = request.with_abort_handle(AbortHandle::new());
+        let response = interruptible(py, || self.inner.send(request))??;
+        Ok(PyBytes::new_bound(py, response.bytes()))
+    }
+}
+
+/// Server-sent events from `url` (like the browser's `EventSource`)
+///
+/// Iterating yields `Event` objects as they arrive. Dropped connections are
+/// reopened with the same settings and a `Last-Event-ID` header; set
+/// `max_retries` to give up after that many failed attempts in a row.
+#[pyclass]
+pub struct EventSource {
+    inner: RustEventSource,
+}
+
+#[pymethods]
+impl EventSource {
+    #[new]
+    #[pyo3(signature = (url, headers=None, impersonate=None, proxies=None, timeout=None, max_retries=None))]
+    fn new(
+        py: Python<'_>,
+        url: &str,
+        headers: Option<&Bound<'_, PyDict>>,
+        impersonate: Option<&str>,
+        proxies: Option<&str>,
+        timeout: Option<f64>,
+        max_retries: Option<u32>,
+    ) -> PyResult<Self> {
+        let request = with_overrides(Request::get(url), headers, impersonate, proxies, timeout)?
+            .with_abort_handle(AbortHandle::new());
+        let mut inner = interruptible(py, || request.event_source())??;
+        inner.set_max_retries(max_retries);
+        Ok(EventSource { inner })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Event>> {
+        let inner = &mut self.inner;
+        match interruptible(py, || inner.next())? {
+            Some(event) => Ok(Some(Event::from(event?))),
+            None => Ok(None),
+        }
+    }
+
+    /// ID of the last event with one, or `None`
+    #[getter]
+    fn last_event_id(&self) -> Option<String> {
+        self.inner.last_event_id().map(str::to_string)
+    }
+
+    /// Drop the connection; iteration stops
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+/// One event from an `EventSource`
+#[pyclass]
+#[derive(Clone)]
+pub struct Event {
+    /// Event type, `"message"` unless the server named one
+    #[pyo3(get)]
+    event: String,
+    #[pyo3(get)]
+    data: String,
+    #[pyo3(get)]
+    id: Option<String>,
+}
+
+#[pymethods]
+impl Event {
+    fn __repr__(&self) -> String {
+        let id = match &self.id {
+            Some(id) => format!("{:?}", id),
+            None => "None".to_string(),
+        };
+        format!(
+            "Event(event={:?}, data={:?}, id={})",
+            self.event, self.data, id
+        )
+    }
+}
+
+impl From<RustEvent> for Event {
+    fn from(event: RustEvent) -> Self {
+        Event {
+            event: event.event,
+            data: event.data,
+            id: event.id,
+        }
+    }
+}
+
+/// Apply the per-request keyword arguments shared by the `Session` methods
+fn with_overrides(
+    mut request: Request,
+    headers: Option<&Bound<'_, PyDict>>,
+    impersonate: Option<&str>,
+    proxies: Option<&str>,
+    timeout: Option<f64>,
+) -> PyResult<Request> {
+    request = request.headers(to_header_map(headers)?);
+    if let Some(browser_str) = impersonate {
+        request = request.impersonate(parse_browser(browser_str)?);
+    }
+    if let Some(proxy) = proxies {
+        request = request.proxies(proxy);
+    }
+    if let Some(secs) = timeout {
+        request = request.timeout(to_duration(secs)?);
+    }
+    Ok(request)
+}
+
+/// Headers from a Python dict, in insertion order
+fn to_header_map(headers: Option<&Bound<'_, PyDict>>) -> PyResult<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers.into_iter().flat_map(|dict| dict.iter()) {
+        map.append(name.extract::<String>()?, value.extract::<String>()?)?;
+    }
+    Ok(map)
+}
+
+/// A timeout in seconds, as Python passes it
+fn to_duration(secs: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(secs)
+        .map_err(|_| PyValueError::new_err(format!("Invalid timeout: {}", secs)))
+}
+
 /// Helper function to parse browser string
 fn parse_browser(impersonate: &str) -> PyResult<Browser> {
     let browser = match impersonate.to_lowercase().as_str() {
@@ -154,8 +519,14 @@ fn parse_browser(impersonate: &str) -> PyResult<Browser> {
 /// * `proxies` - Optional proxy URL (e.g., "http://localhost:3128", "socks5://localhost:1080")
 #[pyfunction]
 #[pyo3(signature = (url, impersonate=None, proxies=None))]
-fn get(url: &str, impersonate: Option<&str>, proxies: Option<&str>) -> PyResult<Vec<u8>> {
+fn get(
+    py: Python<'_>,
+    url: &str,
+    impersonate: Option<&str>,
+    proxies: Option<&str>,
+) -> PyResult<Vec<u8>> {
     let mut curl = RustCurl::new()?;
+    curl.set_abort_handle(AbortHandle::new())?;
 
     if let Some(browser_str) = impersonate {
         let browser = parse_browser(browser_str)?;
@@ -168,7 +539,7 @@ fn get(url: &str, impersonate: Option<&str>, proxies: Option<&str>) -> PyResult<
 
     curl.set_url(url)?;
     let mut buffer = Vec::new();
-    curl.perform(&mut buffer)?;
+    interruptible(py, || curl.perform(&mut buffer))??;
     Ok(buffer)
 }
 
@@ -176,18 +547,22 @@ fn get(url: &str, impersonate: Option<&str>, proxies: Option<&str>) -> PyResult<
 ///
 /// # Arguments
 /// * `url` - The URL to post to
-/// * `data` - The data to POST
+/// * `data` - The data to POST (`bytes` or `str`)
+/// * `multipart` - Optional `Multipart` form, sent instead of `data`
 /// * `impersonate` - Optional browser to impersonate
 /// * `proxies` - Optional proxy URL
 #[pyfunction]
-#[pyo3(signature = (url, data, impersonate=None, proxies=None))]
+#[pyo3(signature = (url, data=None, multipart=None, impersonate=None, proxies=None))]
 fn post(
+    py: Python<'_>,
     url: &str,
-    data: &str,
+    data: Option<PostData<'_>>,
+    multipart: Option<PyRef<'_, Multipart>>,
     impersonate: Option<&str>,
     proxies: Option<&str>,
 ) -> PyResult<Vec<u8>> {
     let mut curl = RustCurl::new()?;
+    curl.set_abort_handle(AbortHandle::new())?;
 
     if let Some(browser_str) = impersonate {
         let browser = parse_browser(browser_str)?;
@@ -199,18 +574,111 @@ fn post(
     }
 
     curl.set_url(url)?;
-    curl.set_post_data(data)?;
+    match (data, multipart) {
+        (Some(_), Some(_)) => {
+            return Err(PyValueError::new_err(
+                "data and multipart can't be used together",
+            ))
+        }
+        (_, Some(form)) => curl.set_multipart(form.to_rust())?,
+        (data, None) => curl.set_post_bytes(data.as_ref().map_or(&[][..], |d| d.as_bytes()))?,
+    }
     let mut buffer = Vec::new();
-    curl.perform(&mut buffer)?;
+    interruptible(py, || curl.perform(&mut buffer))??;
     Ok(buffer)
 }
 
+/// Fetch many URLs at once (GET), a few at a time
+///
+/// Returns one item per URL, in the same order: the body as `bytes`, or
+/// the exception the request failed with, so one failure doesn't lose the
+/// other results.
+///
+/// # Arguments
+/// * `urls` - The URLs to fetch
+/// * `impersonate` - Optional browser to impersonate for every request
+/// * `proxies` - Optional proxy URL
+/// * `timeout` - Optional timeout per request, in seconds
+/// * `headers` - Optional headers sent with every request
+/// * `concurrency` - Requests in flight at once
+/// * `per_host_limit` - Requests in flight to any one host, 0 for no limit
+#[pyfunction]
+#[pyo3(signature = (urls, impersonate=None, proxies=None, timeout=None, headers=None, concurrency=DEFAULT_CONCURRENCY, per_host_limit=0))]
+#[allow(clippy::too_many_arguments)]
+fn batch_get(
+    py: Python<'_>,
+    urls: Vec<String>,
+    impersonate: Option<&str>,
+    proxies: Option<&str>,
+    timeout: Option<f64>,
+    headers: Option<&Bound<'_, PyDict>>,
+    concurrency: usize,
+    per_host_limit: usize,
+) -> PyResult<Vec<PyObject>> {
+    let requests = urls
+        .into_iter()
+        .map(|url| with_overrides(Request::get(url), headers, impersonate, proxies, timeout))
+        .collect::<PyResult<Vec<_>>>()?;
+    let options = BatchOptions {
+        concurrency,
+        per_host_limit,
+    };
+
+    // Ctrl+C aborts all of them
+    let abort = AbortHandle::new();
+    let requests: Vec<_> = requests
+        .into_iter()
+        .map(|request| request.with_abort_handle(abort.clone()))
+        .collect();
+    let results = interruptible(py, || batch(requests, options))?;
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Ok(response) => PyBytes::new_bound(py, response.bytes()).into_any().unbind(),
+            Err(e) => PyErr::from(e).into_value(py).into_any(),
+        })
+        .collect())
+}
+
+/// Features of the linked libcurl, as a dict
+///
+/// Keys: `version`, `ssl_backend`, `http2`, `http3`, `libz`, `cipher_list`,
+/// `tls13_ciphers`, `signature_algorithms`, `http2_fingerprint`,
+/// `sig_hash_algs`, `brotli`, `zstd`, `idn`, `impersonate` and `protocols`.
+#[pyfunction]
+fn capabilities(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let caps = crate::capabilities();
+    let dict = PyDict::new_bound(py);
+    dict.set_item("version", &caps.version)?;
+    dict.set_item("ssl_backend", &caps.ssl_backend)?;
+    dict.set_item("http2", caps.http2)?;
+    dict.set_item("http3", caps.http3)?;
+    dict.set_item("libz", caps.libz)?;
+    dict.set_item("cipher_list", caps.cipher_list)?;
+    dict.set_item("tls13_ciphers", caps.tls13_ciphers)?;
+    dict.set_item("signature_algorithms", caps.signature_algorithms)?;
+    dict.set_item("http2_fingerprint", caps.http2_fingerprint)?;
+    dict.set_item("sig_hash_algs", caps.sig_hash_algs)?;
+    dict.set_item("brotli", caps.brotli)?;
+    dict.set_item("zstd", caps.zstd)?;
+    dict.set_item("idn", caps.idn)?;
+    dict.set_item("impersonate", caps.impersonate)?;
+    dict.set_item("protocols", &caps.protocols)?;
+    Ok(dict)
+}
+
 /// Initialize the Python module
 #[pymodule]
 fn hyprcurl(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Curl>()?;
+    m.add_class::<Multipart>()?;
+    m.add_class::<Session>()?;
+    m.add_class::<EventSource>()?;
+    m.add_class::<Event>()?;
     m.add_function(wrap_pyfunction!(get, m)?)?;
     m.add_function(wrap_pyfunction!(post, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_get, m)?)?;
+    m.add_function(wrap_pyfunction!(capabilities, m)?)?;
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     Ok(())
 }
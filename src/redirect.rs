@@ -0,0 +1,240 @@
+//! Redirect policies: how many `Location` responses to follow, and where
+
+use crate::error::{CurlError, Result};
+use std::fmt;
+use std::sync::Arc;
+
+/// Default number of redirects followed, the same as libcurl's
+pub const DEFAULT_MAX_REDIRECTS: u32 = 30;
+
+/// How redirects are followed
+///
+/// `None` and `Limited` are handled by libcurl itself. `SameOrigin` and
+/// `Custom` are followed hop by hop by this crate, which gives them the same
+/// [`Response::history`](crate::Response::history) and header stripping.
+///
+/// On every hop to another origin (scheme, host and port), the
+/// `Authorization` and `Cookie` request headers are dropped. Cookies stored
+/// in the handle's cookie engine are still sent where their domain matches.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{RedirectPolicy, Request};
+///
+/// // Only follow redirects that stay on the same site
+/// let response = Request::get("https://httpbin.org/redirect/2")
+///     .redirect_policy(RedirectPolicy::SameOrigin(5))
+///     .send()
+///     .unwrap();
+///
+/// // Decide per hop
+/// let policy = RedirectPolicy::custom(|attempt| {
+///     attempt.previous() < 3 && !attempt.next_url().contains("/login")
+/// });
+/// let response = Request::get("https://httpbin.org/redirect/2")
+///     .redirect_policy(policy)
+///     .send()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub enum RedirectPolicy {
+    /// Don't follow redirects; the 3xx response is returned as is
+    None,
+    /// Follow up to this many redirects, then fail with
+    /// `CURLE_TOO_MANY_REDIRECTS`
+    Limited(u32),
+    /// Follow up to this many redirects that keep the scheme, host and port
+    ///
+    /// A redirect to another origin isn't followed: its 3xx response is
+    /// returned instead.
+    SameOrigin(u32),
+    /// Ask a callback about every redirect; `true` follows it
+    ///
+    /// There is no limit besides the callback, which can check
+    /// [`Attempt::previous`].
+    Custom(Arc<dyn Fn(&Attempt<'_>) -> bool + Send + Sync>),
+}
+
+impl RedirectPolicy {
+    /// Build a [`Custom`](Self::Custom) policy from a closure
+    pub fn custom<F>(decide: F) -> Self
+    where
+        F: Fn(&Attempt<'_>) -> bool + Send + Sync + 'static,
+    {
+        RedirectPolicy::Custom(Arc::new(decide))
+    }
+
+    /// Whether libcurl follows redirects itself under this policy
+    pub(crate) fn is_native(&self) -> bool {
+        matches!(self, RedirectPolicy::None | RedirectPolicy::Limited(_))
+    }
+
+    /// Whether to follow a redirect this crate follows by hand
+    pub(crate) fn allows(&self, attempt: &Attempt<'_>) -> Result<bool> {
+        match self {
+            RedirectPolicy::None | RedirectPolicy::Limited(_) => Ok(false),
+            RedirectPolicy::SameOrigin(_) if !attempt.same_origin => Ok(false),
+            RedirectPolicy::SameOrigin(max) if attempt.previous >= *max as usize => Err(
+                CurlError::from_curl_code(curl_sys::CURLE_TOO_MANY_REDIRECTS),
+            ),
+            RedirectPolicy::SameOrigin(_) => Ok(true),
+            RedirectPolicy::Custom(decide) => Ok(decide(attempt)),
+        }
+    }
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Limited(DEFAULT_MAX_REDIRECTS)
+    }
+}
+
+impl fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedirectPolicy::None => f.write_str("None"),
+            RedirectPolicy::Limited(max) => f.debug_tuple("Limited").field(max).finish(),
+            RedirectPolicy::SameOrigin(max) => f.debug_tuple("SameOrigin").field(max).finish(),
+            RedirectPolicy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A redirect about to be followed, as seen by [`RedirectPolicy::Custom`]
+#[derive(Debug, Clone)]
+pub struct Attempt<'a> {
+    pub(crate) status: u16,
+    pub(crate) url: &'a str,
+    pub(crate) next_url: &'a str,
+    pub(crate) previous: usize,
+    pub(crate) same_origin: bool,
+}
+
+impl Attempt<'_> {
+    /// Status code of the redirect response
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// URL that answered with the redirect
+    pub fn url(&self) -> &str {
+        self.url
+    }
+
+    /// Absolute URL the redirect points to
+    pub fn next_url(&self) -> &str {
+        self.next_url
+    }
+
+    /// Number of redirects already followed for this request
+    pub fn previous(&self) -> usize {
+        self.previous
+    }
+
+    /// Whether the next URL has the same scheme, host and port
+    pub fn is_same_origin(&self) -> bool {
+        self.same_origin
+    }
+}
+
+/// Which redirects keep a POST a POST (`CURLOPT_POSTREDIR`)
+///
+/// By default a POST becomes a bodyless GET after a 301, 302 or 303, like
+/// browsers do. 307 and 308 always keep the method and body.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{PostRedirect, Request};
+///
+/// let response = Request::post("https://httpbin.org/redirect-to?url=/post", "data")
+///     .post_redirect(PostRedirect { on_302: true, ..PostRedirect::default() })
+///     .send()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PostRedirect {
+    /// Keep the POST after a 301 Moved Permanently
+    pub on_301: bool,
+    /// Keep the POST after a 302 Found
+    pub on_302: bool,
+    /// Keep the POST after a 303 See Other
+    pub on_303: bool,
+}
+
+impl PostRedirect {
+    /// Keep the POST after every redirect status
+    pub const ALL: PostRedirect = PostRedirect {
+        on_301: true,
+        on_302: true,
+        on_303: true,
+    };
+
+    /// Bitmask for `CURLOPT_POSTREDIR`
+    pub(crate) fn to_curl(self) -> i64 {
+        let mut bits = 0;
+        if self.on_301 {
+            bits |= curl_sys::CURL_REDIR_POST_301;
+        }
+        if self.on_302 {
+            bits |= curl_sys::CURL_REDIR_POST_302;
+        }
+        if self.on_303 {
+            bits |= curl_sys::CURL_REDIR_POST_303;
+        }
+        bits as i64
+    }
+
+    /// Whether a POST stays a POST after a redirect with `status`
+    pub(crate) fn keeps_post(self, status: u16) -> bool {
+        match status {
+            301 => self.on_301,
+            302 => self.on_302,
+            303 => self.on_303,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(previous: usize, same_origin: bool) -> Attempt<'static> {
+        Attempt {
+            status: 302,
+            url: "https://example.com/a",
+            next_url: "https://example.com/b",
+            previous,
+            same_origin,
+        }
+    }
+
+    #[test]
+    fn test_same_origin_policy() {
+        let policy = RedirectPolicy::SameOrigin(2);
+        assert!(policy.allows(&attempt(0, true)).unwrap());
+        assert!(!policy.allows(&attempt(0, false)).unwrap());
+        assert!(policy.allows(&attempt(2, true)).is_err());
+    }
+
+    #[test]
+    fn test_custom_policy_sees_attempt() {
+        let policy = RedirectPolicy::custom(|a| a.previous() < 1 && a.next_url().ends_with("/b"));
+        assert!(!policy.is_native());
+        assert!(policy.allows(&attempt(0, false)).unwrap());
+        assert!(!policy.allows(&attempt(1, false)).unwrap());
+    }
+
+    #[test]
+    fn test_post_redirect_bits() {
+        assert_eq!(PostRedirect::default().to_curl(), 0);
+        assert_eq!(PostRedirect::ALL.to_curl(), 7);
+        let keep_302 = PostRedirect {
+            on_302: true,
+            ..PostRedirect::default()
+        };
+        assert!(keep_302.keeps_post(302));
+        assert!(!keep_302.keeps_post(301));
+        assert!(keep_302.keeps_post(307));
+    }
+}
@@ -0,0 +1,391 @@
+//! HTTP response type returned by the high-level API
+
+use crate::curl::Curl;
+use crate::error::{CurlError, Result};
+use crate::header::HeaderMap;
+use crate::types::{CurlInfo, HttpVersion};
+use std::time::Duration;
+
+/// Timing breakdown of a completed transfer
+///
+/// Every value is measured from the start of the transfer, as reported by
+/// the corresponding `CURLINFO_*_TIME` field.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    /// Time until name resolution completed
+    pub name_lookup: Duration,
+    /// Time until the TCP connection was established
+    pub connect: Duration,
+    /// Time until the TLS handshake completed (zero for plain HTTP)
+    pub app_connect: Duration,
+    /// Time until the request was about to be sent
+    pub pre_transfer: Duration,
+    /// Time until the first response byte arrived
+    pub start_transfer: Duration,
+    /// Time spent following redirects before the final transfer
+    pub redirect: Duration,
+    /// Total time of the transfer
+    pub total: Duration,
+}
+
+impl Timings {
+    fn from_curl(curl: &Curl) -> Result<Self> {
+        let get = |info| curl.getinfo_double(info).map(secs_to_duration);
+        Ok(Timings {
+            name_lookup: get(CurlInfo::NameLookupTime)?,
+            connect: get(CurlInfo::ConnectTime)?,
+            app_connect: get(CurlInfo::AppConnectTime)?,
+            pre_transfer: get(CurlInfo::PreTransferTime)?,
+            start_transfer: get(CurlInfo::StartTransferTime)?,
+            redirect: get(CurlInfo::RedirectTime)?,
+            total: get(CurlInfo::TotalTime)?,
+        })
+    }
+}
+
+fn secs_to_duration(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or_default()
+}
+
+/// One intermediate response of a followed redirect chain
+///
+/// See [`Response::history`].
+#[derive(Debug, Clone)]
+pub struct Hop {
+    url: String,
+    status: u16,
+    headers: HeaderMap,
+}
+
+impl Hop {
+    /// URL that was requested for this hop
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Status code of this hop, e.g. 301 or 302
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Headers of this hop, including any `Set-Cookie` it sent
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The `Location` this hop redirected to, as sent by the server
+    pub fn location(&self) -> Option<&str> {
+        self.headers.get("location")
+    }
+}
+
+/// A completed HTTP response
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::Request;
+///
+/// let response = Request::get("https://httpbin.org/get").send().unwrap();
+/// println!("{} from {}", response.status(), response.url());
+/// if let Some(ct) = response.header("content-type") {
+///     println!("Content-Type: {}", ct);
+/// }
+/// println!("{}", response.text().unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    url: String,
+    http_version: Option<HttpVersion>,
+    timings: Timings,
+    history: Vec<Hop>,
+}
+
+impl Response {
+    /// Build a response from a finished transfer on `curl`
+    pub(crate) fn from_transfer(
+        curl: &Curl,
+        body: Vec<u8>,
+        header_lines: &[String],
+    ) -> Result<Self> {
+        let status = u16::try_from(curl.response_code()?).unwrap_or_default();
+        let mut blocks = parse_header_blocks(header_lines);
+        let headers = blocks.pop().map(|(_, headers)| headers).unwrap_or_default();
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+            url: curl.effective_url()?,
+            http_version: curl.http_version()?,
+            timings: Timings::from_curl(curl)?,
+            history: build_history(curl.request_url().unwrap_or_default(), blocks),
+        })
+    }
+
+    /// HTTP status code of the final response
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Whether the status code is in the 2xx range
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Response headers of the final response, in the order they were received
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get the first value of a header (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)
+    }
+
+    /// Response body as raw bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Consume the response and return the body
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.body
+    }
+
+    /// Response body decoded as UTF-8
+    pub fn text(&self) -> Result<String> {
+        Ok(std::str::from_utf8(&self.body)?.to_owned())
+    }
+
+    /// Deserialize the response body as JSON
+    ///
+    /// On failure the error carries the start of the body, which usually
+    /// explains the problem (an HTML error page, say).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    /// use std::collections::HashMap;
+    ///
+    /// let response = Request::get("https://httpbin.org/get").send().unwrap();
+    /// let body: HashMap<String, serde_json::Value> = response.json().unwrap();
+    /// println!("{:?}", body["headers"]);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body).map_err(|source| CurlError::JsonDecode {
+            source,
+            snippet: body_snippet(&self.body),
+        })
+    }
+
+    /// Final URL after redirects
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Redirect hops that led to this response, oldest first
+    ///
+    /// Empty when the request wasn't redirected.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let response = Request::get("https://httpbin.org/redirect/3").send().unwrap();
+    /// for hop in response.history() {
+    ///     println!("{} {} -> {:?}", hop.status(), hop.url(), hop.location());
+    /// }
+    /// ```
+    pub fn history(&self) -> &[Hop] {
+        &self.history
+    }
+
+    /// HTTP version negotiated with the server
+    pub fn http_version(&self) -> Option<HttpVersion> {
+        self.http_version
+    }
+
+    /// Timing breakdown of the transfer
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    /// Turn 4xx and 5xx responses into an error
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::Request;
+    ///
+    /// let result = Request::get("https://httpbin.org/status/404")
+    ///     .send()
+    ///     .and_then(|r| r.error_for_status());
+    /// assert!(result.is_err());
+    /// ```
+    pub fn error_for_status(self) -> Result<Self> {
+        if (400..600).contains(&self.status) {
+            Err(CurlError::HttpStatus {
+                status: self.status,
+                url: self.url,
+            })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// How much of the body a JSON decode error quotes
+#[cfg(feature = "json")]
+const SNIPPET_LEN: usize = 200;
+
+/// Start of the body as text, cut at a character boundary
+#[cfg(feature = "json")]
+fn body_snippet(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&body[..body.len().min(SNIPPET_LEN)]);
+    // A multi-byte character split by the cut decodes as U+FFFD; drop it
+    text.trim_end_matches('\u{FFFD}').to_string()
+}
+
+/// Split raw header lines into one `(status, headers)` block per response
+///
+/// libcurl reports the headers of every response it sees, so redirect hops
+/// each get a block. Interim 1xx responses are dropped.
+pub(crate) fn parse_header_blocks(lines: &[String]) -> Vec<(u16, HeaderMap)> {
+    let mut blocks: Vec<(u16, HeaderMap)> = Vec::new();
+    for line in lines {
+        if line.starts_with("HTTP/") {
+            let status = line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|code| code.parse().ok())
+                .unwrap_or_default();
+            // A 1xx block is always followed by the real response
+            if blocks.last().is_some_and(|(s, _)| (100..200).contains(s)) {
+                blocks.pop();
+            }
+            blocks.push((status, HeaderMap::new()));
+        } else if let Some((name, value)) = line.split_once(':') {
+            if blocks.is_empty() {
+                blocks.push((0, HeaderMap::new()));
+            }
+            if let Some((_, headers)) = blocks.last_mut() {
+                headers.append_unchecked(name.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    blocks
+}
+
+/// Parse raw header lines into the [`HeaderMap`] of the final response
+pub(crate) fn parse_header_lines(lines: &[String]) -> HeaderMap {
+    parse_header_blocks(lines)
+        .pop()
+        .map(|(_, headers)| headers)
+        .unwrap_or_default()
+}
+
+/// Turn the header blocks before the final response into hops, following
+/// each `Location` from the original request URL
+fn build_history(request_url: String, blocks: Vec<(u16, HeaderMap)>) -> Vec<Hop> {
+    let mut url = request_url;
+    blocks
+        .into_iter()
+        .map(|(status, headers)| {
+            let next = match headers.get("location") {
+                Some(location) => {
+                    crate::url::resolve(&url, location).unwrap_or_else(|_| location.to_string())
+                }
+                // Auth retries and the like stay on the same URL
+                None => url.clone(),
+            };
+            Hop {
+                url: std::mem::replace(&mut url, next),
+                status,
+                headers,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_lines_keeps_last_block() {
+        let lines: Vec<String> = [
+            "HTTP/1.1 302 Found",
+            "Location: /get",
+            "HTTP/1.1 200 OK",
+            "Content-Type: application/json",
+            "X-Empty:",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let headers = parse_header_lines(&lines);
+        let pairs: Vec<_> = headers.iter().collect();
+        assert_eq!(
+            pairs,
+            [("Content-Type", "application/json"), ("X-Empty", "")]
+        );
+    }
+
+    #[test]
+    fn test_history_from_header_blocks() {
+        let lines: Vec<String> = [
+            "HTTP/1.1 302 Found",
+            "Location: /step2",
+            "Set-Cookie: a=1",
+            "HTTP/1.1 301 Moved Permanently",
+            "Location: https://other.example/final",
+            "HTTP/1.1 100 Continue",
+            "HTTP/1.1 200 OK",
+            "Content-Type: text/plain",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut blocks = parse_header_blocks(&lines);
+        let (status, headers) = blocks.pop().unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(headers.get("content-type"), Some("text/plain"));
+
+        let history = build_history("https://example.com/start".to_string(), blocks);
+        let hops: Vec<_> = history.iter().map(|h| (h.status(), h.url())).collect();
+        assert_eq!(
+            hops,
+            [
+                (302, "https://example.com/start"),
+                (301, "https://example.com/step2")
+            ]
+        );
+        assert_eq!(history[0].headers().get("set-cookie"), Some("a=1"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_decode_error_has_snippet() {
+        let response = Response {
+            status: 502,
+            headers: HeaderMap::new(),
+            body: b"<html>Bad Gateway</html>".to_vec(),
+            url: "https://example.com/api".to_string(),
+            http_version: None,
+            timings: Timings::default(),
+            history: Vec::new(),
+        };
+
+        match response.json::<serde_json::Value>() {
+            Err(CurlError::JsonDecode { snippet, .. }) => {
+                assert_eq!(snippet, "<html>Bad Gateway</html>")
+            }
+            other => panic!("expected JsonDecode, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Sessions: one reusable handle with cookies, connections and defaults
+
+use crate::cookie::CookieJar;
+use crate::curl::Curl;
+use crate::error::{CurlError, Result};
+use crate::header::HeaderMap;
+use crate::redirect::RedirectPolicy;
+use crate::response::Response;
+use crate::share::{Share, ShareData};
+use crate::types::{Browser, Method};
+use crate::Request;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// A series of requests sharing cookies, connections and defaults
+///
+/// Equivalent to curl_cffi's `requests.Session`. Every request goes through
+/// the same handle, so connections are kept alive, TLS sessions are resumed
+/// and cookies set by responses are sent back on later requests.
+///
+/// Defaults set on the session (headers, impersonation, proxy, timeouts and
+/// redirect policy) apply to every request; anything set on the [`Request`]
+/// itself wins. Requests from several threads are sent one at a time.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, Request, Session};
+/// use std::time::Duration;
+///
+/// let session = Session::new()
+///     .unwrap()
+///     .impersonate(Browser::ChromeLatest)
+///     .timeout(Duration::from_secs(30));
+///
+/// // The session cookie set here is sent with the next request
+/// session.post("https://example.com/login", "user=jane&pass=secret").unwrap();
+/// let profile = session.get("https://example.com/profile").unwrap();
+///
+/// // Per-request overrides
+/// let response = Request::get("https://example.com/api")
+///     .header("Accept", "application/json")
+///     .send_with(&session)
+///     .unwrap();
+/// ```
+pub struct Session {
+    curl: Mutex<Curl>,
+    cookies: CookieJar,
+    share: Option<Share>,
+    headers: HeaderMap,
+    impersonate: Option<Browser>,
+    strict_impersonation: Option<bool>,
+    proxies: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    redirect_policy: Option<RedirectPolicy>,
+}
+
+impl Session {
+    /// Create a session with an empty cookie store and no defaults
+    pub fn new() -> Result<Self> {
+        Ok(Session {
+            curl: Mutex::new(Curl::new()?),
+            cookies: CookieJar::new(),
+            share: None,
+            headers: HeaderMap::new(),
+            impersonate: None,
+            strict_impersonation: None,
+            proxies: None,
+            timeout: None,
+            connect_timeout: None,
+            redirect_policy: None,
+        })
+    }
+
+    /// Keep cookies in this jar, for example one shared with other sessions
+    pub fn cookie_jar(mut self, jar: CookieJar) -> Self {
+        self.cookies = jar;
+        self
+    }
+
+    /// The session's cookies, to read, change or save them
+    pub fn cookies(&self) -> &CookieJar {
+        &self.cookies
+    }
+
+    /// Use the DNS cache, TLS sessions and connections of `share`
+    ///
+    /// Sessions on different threads with the same share resolve each host
+    /// once and reuse each other's connections.
+    ///
+    /// # Errors
+    /// `InvalidOption` if the share shares cookies: those stay in the
+    /// session's [`CookieJar`], which can be shared instead.
+    pub fn share(mut self, share: Share) -> Result<Self> {
+        if share.shares(ShareData::Cookies) {
+            return Err(CurlError::InvalidOption(
+                "a session's share can't share cookies; share its CookieJar instead".to_string(),
+            ));
+        }
+        self.share = Some(share);
+        Ok(self)
+    }
+
+    /// Send these headers with every request
+    ///
+    /// A request header with the same name replaces the session's.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Default headers, for changing them after the session is built
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Impersonate this browser unless a request picks another one
+    pub fn impersonate(mut self, browser: Browser) -> Self {
+        self.impersonate = Some(browser);
+        self
+    }
+
+    /// Fail requests whose impersonation libcurl can't reproduce fully,
+    /// unless a request says otherwise
+    pub fn strict_impersonation(mut self, strict: bool) -> Self {
+        self.strict_impersonation = Some(strict);
+        self
+    }
+
+    /// Send every request through this proxy unless it sets its own
+    pub fn proxies(mut self, proxy: impl Into<String>) -> Self {
+        self.proxies = Some(proxy.into());
+        self
+    }
+
+    /// Default total timeout of each request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Default connect timeout of each request
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Default redirect policy of each request
+    pub fn redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = Some(policy);
+        self
+    }
+
+    /// Send a request with the session's cookies, connections and defaults
+    pub fn send(&self, mut request: Request) -> Result<Response> {
+        self.fill_defaults(&mut request);
+
+        // A panic while holding the lock leaves nothing half-done: the
+        // handle is reset before every request
+        let mut curl = self.curl.lock().unwrap_or_else(PoisonError::into_inner);
+        curl.reset();
+        if let Some(share) = &self.share {
+            curl.set_share(share)?;
+        }
+        curl.set_cookie_jar(self.cookies.clone())?;
+        request.configure(&mut curl)?;
+        curl.perform_response()
+    }
+
+    /// Send a GET request
+    pub fn get(&self, url: impl Into<String>) -> Result<Response> {
+        self.send(Request::get(url))
+    }
+
+    /// Send a POST request with a body
+    pub fn post(&self, url: impl Into<String>, data: impl Into<Vec<u8>>) -> Result<Response> {
+        self.send(Request::post(url, data))
+    }
+
+    /// Send a request with any method and no body
+    pub fn request(&self, method: impl Into<Method>, url: impl Into<String>) -> Result<Response> {
+        self.send(Request::new(method, url))
+    }
+
+    /// Fill in whatever the request leaves to the session
+    fn fill_defaults(&self, request: &mut Request) {
+        let mut headers = self.headers.clone();
+        headers.merge(std::mem::take(&mut request.headers));
+        request.headers = headers;
+
+        if request.impersonate.is_none() {
+            request.impersonate = self.impersonate.clone();
+        }
+        if request.strict_impersonation.is_none() {
+            request.strict_impersonation = self.strict_impersonation;
+        }
+        if request.proxies.is_none() {
+            request.proxies = self.proxies.clone();
+        }
+        if request.timeout.is_none() {
+            request.timeout = self.timeout;
+        }
+        if request.connect_timeout.is_none() {
+            request.connect_timeout = self.connect_timeout;
+        }
+        if request.redirect_policy.is_none() {
+            request.redirect_policy = self.redirect_policy.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_overrides_session_defaults() {
+        let mut defaults = HeaderMap::new();
+        defaults.append("User-Agent", "session").unwrap();
+        defaults.append("X-Session", "1").unwrap();
+        let session = Session::new()
+            .unwrap()
+            .headers(defaults)
+            .proxies("http://session-proxy:3128")
+            .timeout(Duration::from_secs(5));
+
+        let mut request = Request::get("https://example.com")
+            .header("user-agent", "request")
+            .timeout(Duration::from_secs(1));
+        session.fill_defaults(&mut request);
+
+        let pairs: Vec<_> = request.headers.iter().collect();
+        assert_eq!(pairs, [("user-agent", "request"), ("X-Session", "1")]);
+        assert_eq!(
+            request.proxies.as_deref(),
+            Some("http://session-proxy:3128")
+        );
+        assert_eq!(request.timeout, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_share_must_not_share_cookies() {
+        let session = Session::new().unwrap();
+        let cookies = Share::with(&[ShareData::Cookies, ShareData::Dns]).unwrap();
+        assert!(matches!(
+            session.share(cookies),
+            Err(CurlError::InvalidOption(_))
+        ));
+        assert!(Session::new().unwrap().share(Share::new().unwrap()).is_ok());
+    }
+}
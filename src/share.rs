@@ -0,0 +1,274 @@
+//! Caches shared between handles through libcurl's `curl_share` interface
+
+use crate::error::{CurlError, Result};
+use std::fmt;
+use std::os::raw::c_void;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+/// Default number of connections a [`Share`] keeps open
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 100;
+
+/// `CURL_LOCK_DATA_LAST`: one lock for each kind of data libcurl may lock
+const LOCK_COUNT: usize = 8;
+
+/// Data a [`Share`] holds for all of its handles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareData {
+    /// Cookies; can't be combined with a [`CookieJar`](crate::CookieJar)
+    Cookies,
+    /// Resolved host names
+    Dns,
+    /// TLS session tickets, so later handshakes are resumed
+    SslSessions,
+    /// Open connections, reused by any handle going to the same host
+    Connections,
+}
+
+impl ShareData {
+    fn to_curl(self) -> curl_sys::curl_lock_data {
+        match self {
+            ShareData::Cookies => curl_sys::CURL_LOCK_DATA_COOKIE,
+            ShareData::Dns => curl_sys::CURL_LOCK_DATA_DNS,
+            ShareData::SslSessions => curl_sys::CURL_LOCK_DATA_SSL_SESSION,
+            ShareData::Connections => curl_sys::CURL_LOCK_DATA_CONNECT,
+        }
+    }
+}
+
+/// Lock that libcurl takes and releases in two separate callbacks
+///
+/// A `MutexGuard` can't outlive the lock callback, so the mutex only guards
+/// the `locked` flag.
+#[derive(Default)]
+struct Lock {
+    locked: Mutex<bool>,
+    released: Condvar,
+}
+
+impl Lock {
+    fn acquire(&self) {
+        let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+        while *locked {
+            locked = self
+                .released
+                .wait(locked)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *locked = true;
+    }
+
+    fn release(&self) {
+        *self.locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        self.released.notify_one();
+    }
+}
+
+struct ShareInner {
+    handle: *mut curl_sys::CURLSH,
+    locks: [Lock; LOCK_COUNT],
+    data: Vec<ShareData>,
+}
+
+// libcurl calls back into `locks` for every access to the share, from any
+// thread
+unsafe impl Send for ShareInner {}
+unsafe impl Sync for ShareInner {}
+
+impl Drop for ShareInner {
+    fn drop(&mut self) {
+        // Handles keep the share alive, so none of them uses it anymore
+        unsafe {
+            curl_sys::curl_share_cleanup(self.handle);
+        }
+    }
+}
+
+/// DNS cache, TLS sessions, connections and cookies shared between handles
+///
+/// Handles on any number of threads can use the same share with
+/// [`Curl::set_share`](crate::Curl::set_share) or
+/// [`Session::share`](crate::Session::share), so a host is resolved once,
+/// later TLS handshakes are resumed and idle connections are reused by
+/// whichever handle goes to that host next. Clones refer to the same share.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Session, Share};
+/// use std::thread;
+///
+/// let share = Share::new().unwrap();
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let session = Session::new().unwrap().share(share.clone()).unwrap();
+///         thread::spawn(move || session.get(format!("https://example.com/item/{}", i)))
+///     })
+///     .collect();
+/// for worker in workers {
+///     worker.join().unwrap().unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Share {
+    inner: Arc<ShareInner>,
+    max_connections: u32,
+}
+
+impl Share {
+    /// Share the DNS cache, TLS sessions and connections
+    ///
+    /// Cookies are left out, so each [`Session`](crate::Session) keeps its
+    /// own. Use [`with`](Self::with) to share them too.
+    pub fn new() -> Result<Self> {
+        Self::with(&[
+            ShareData::Dns,
+            ShareData::SslSessions,
+            ShareData::Connections,
+        ])
+    }
+
+    /// Share exactly these kinds of data
+    pub fn with(data: &[ShareData]) -> Result<Self> {
+        crate::init_curl();
+        let handle = unsafe { curl_sys::curl_share_init() };
+        if handle.is_null() {
+            return Err(CurlError::InitError);
+        }
+
+        let mut unique = Vec::new();
+        for kind in data {
+            if !unique.contains(kind) {
+                unique.push(*kind);
+            }
+        }
+        let inner = Arc::new(ShareInner {
+            handle,
+            locks: Default::default(),
+            data: unique,
+        });
+
+        let lock: curl_sys::curl_lock_function = lock_func;
+        let unlock: curl_sys::curl_unlock_function = unlock_func;
+        let userdata = Arc::as_ptr(&inner) as *mut c_void;
+        unsafe {
+            check_share_code(curl_sys::curl_share_setopt(
+                handle,
+                curl_sys::CURLSHOPT_LOCKFUNC,
+                lock,
+            ))?;
+            check_share_code(curl_sys::curl_share_setopt(
+                handle,
+                curl_sys::CURLSHOPT_UNLOCKFUNC,
+                unlock,
+            ))?;
+            check_share_code(curl_sys::curl_share_setopt(
+                handle,
+                curl_sys::CURLSHOPT_USERDATA,
+                userdata,
+            ))?;
+            for kind in &inner.data {
+                check_share_code(curl_sys::curl_share_setopt(
+                    handle,
+                    curl_sys::CURLSHOPT_SHARE,
+                    kind.to_curl(),
+                ))?;
+            }
+        }
+
+        Ok(Share {
+            inner,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    /// Keep up to this many connections open across all handles
+    ///
+    /// Each handle closes the oldest idle connection when it finishes a
+    /// transfer with more than this many open, so the limit should be
+    /// at least the number of handles running at once. Applies to
+    /// handles that join the share afterwards.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Connection limit handed to each handle (`CURLOPT_MAXCONNECTS`)
+    pub(crate) fn connection_limit(&self) -> u32 {
+        self.max_connections
+    }
+
+    /// Whether this kind of data is shared
+    pub fn shares(&self, data: ShareData) -> bool {
+        self.inner.data.contains(&data)
+    }
+
+    /// Get raw share handle (for advanced use)
+    pub fn raw_handle(&self) -> *mut curl_sys::CURLSH {
+        self.inner.handle
+    }
+}
+
+impl fmt::Debug for Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("data", &self.inner.data)
+            .field("max_connections", &self.max_connections)
+            .finish()
+    }
+}
+
+/// Check a CURLSHcode and convert to Result
+fn check_share_code(code: curl_sys::CURLSHcode) -> Result<()> {
+    if code == curl_sys::CURLSHE_OK {
+        Ok(())
+    } else {
+        Err(CurlError::from_share_code(code))
+    }
+}
+
+extern "C" fn lock_func(
+    _handle: *mut curl_sys::CURL,
+    data: curl_sys::curl_lock_data,
+    _access: curl_sys::curl_lock_access,
+    userptr: *mut c_void,
+) {
+    // Shared (read) access is rare enough that every lock is exclusive
+    let inner = unsafe { &*(userptr as *const ShareInner) };
+    if let Some(lock) = inner.locks.get(data as usize) {
+        lock.acquire();
+    }
+}
+
+extern "C" fn unlock_func(
+    _handle: *mut curl_sys::CURL,
+    data: curl_sys::curl_lock_data,
+    userptr: *mut c_void,
+) {
+    let inner = unsafe { &*(userptr as *const ShareInner) };
+    if let Some(lock) = inner.locks.get(data as usize) {
+        lock.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CookieJar, Curl};
+
+    #[test]
+    fn test_share_data() {
+        let share = Share::new().unwrap();
+        assert!(share.shares(ShareData::Connections));
+        assert!(!share.shares(ShareData::Cookies));
+
+        let cookies = Share::with(&[ShareData::Cookies]).unwrap();
+        let mut curl = Curl::new().unwrap();
+        curl.set_share(&cookies).unwrap();
+        assert!(curl.set_cookie_jar(CookieJar::new()).is_err());
+
+        // The share outlives the handles that use it
+        let mut curl = Curl::new().unwrap();
+        curl.set_share(&share).unwrap();
+        drop(share);
+        curl.reset();
+    }
+}
@@ -0,0 +1,414 @@
+//! Server-sent events (`text/event-stream`) read from a streaming response
+
+use crate::curl::Curl;
+use crate::error::{CurlError, Result};
+use crate::stream::ResponseStream;
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Wait before reconnecting, until the server sends a `retry:` field
+pub const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+/// How often the wait before reconnecting checks for an abort
+const ABORT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An event dispatched by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// `event:` field, `"message"` when the server didn't send one
+    pub event: String,
+    /// `data:` lines joined with `\n`
+    pub data: String,
+    /// Last event ID set by the stream when the event was dispatched
+    pub id: Option<String>,
+}
+
+/// Incremental `text/event-stream` parser, following the WHATWG HTML
+/// standard's "interpreting an event stream"
+struct Parser {
+    /// Bytes of a line whose end hasn't arrived
+    line: Vec<u8>,
+    /// The last chunk ended in CR, so a leading LF belongs to that line end
+    after_cr: bool,
+    /// No line seen yet on this connection, so a BOM may come first
+    at_start: bool,
+    event: String,
+    data: String,
+    /// `id:` field, which becomes the last event ID at the next blank line
+    id: String,
+    last_event_id: String,
+    /// `retry:` field not picked up yet
+    retry: Option<Duration>,
+}
+
+impl Parser {
+    fn new() -> Self {
+        Parser {
+            line: Vec::new(),
+            after_cr: false,
+            at_start: true,
+            event: String::new(),
+            data: String::new(),
+            id: String::new(),
+            last_event_id: String::new(),
+            retry: None,
+        }
+    }
+
+    /// Forget the half-received event of a connection that ended
+    fn restart(&mut self) {
+        self.line.clear();
+        self.after_cr = false;
+        self.at_start = true;
+        self.event.clear();
+        self.data.clear();
+        self.id.clone_from(&self.last_event_id);
+    }
+
+    fn feed(&mut self, mut chunk: &[u8], events: &mut VecDeque<Event>) {
+        if self.after_cr {
+            self.after_cr = false;
+            chunk = chunk.strip_prefix(b"\n").unwrap_or(chunk);
+        }
+        // Lines end with CRLF, LF or CR
+        while let Some(end) = chunk.iter().position(|&b| b == b'\n' || b == b'\r') {
+            self.line.extend_from_slice(&chunk[..end]);
+            let line = std::mem::take(&mut self.line);
+            self.process_line(&line, events);
+
+            let crlf = chunk[end] == b'\r' && chunk.get(end + 1) == Some(&b'\n');
+            if chunk[end] == b'\r' && end + 1 == chunk.len() {
+                self.after_cr = true;
+            }
+            chunk = &chunk[end + if crlf { 2 } else { 1 }..];
+        }
+        self.line.extend_from_slice(chunk);
+    }
+
+    fn process_line(&mut self, line: &[u8], events: &mut VecDeque<Event>) {
+        let line = String::from_utf8_lossy(line);
+        let mut line = &*line;
+        if self.at_start {
+            self.at_start = false;
+            line = line.strip_prefix('\u{feff}').unwrap_or(line);
+        }
+
+        if line.is_empty() {
+            self.dispatch(events);
+            return;
+        }
+        // Comment, often sent to keep the connection alive
+        if line.starts_with(':') {
+            return;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self, events: &mut VecDeque<Event>) {
+        self.last_event_id.clone_from(&self.id);
+        let event = std::mem::take(&mut self.event);
+        if self.data.is_empty() {
+            return;
+        }
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+        events.push_back(Event {
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data,
+            id: (!self.last_event_id.is_empty()).then(|| self.last_event_id.clone()),
+        });
+    }
+}
+
+/// Reader for a server-sent events stream
+///
+/// Iterating yields events as the server sends them. When the connection
+/// drops, the same request is sent again after the retry delay, with the
+/// `Last-Event-ID` header telling the server where to resume, so the
+/// impersonation, proxy and headers of the original request carry over.
+///
+/// Only network errors are retried. A status other than 200, or a response
+/// that isn't `text/event-stream`, ends the iterator with an error, and a
+/// `204 No Content` ends it without one.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::{Browser, Request};
+///
+/// let events = Request::get("https://example.com/updates")
+///     .impersonate(Browser::ChromeLatest)
+///     .event_source()
+///     .unwrap();
+/// for event in events {
+///     let event = event.unwrap();
+///     println!("{}: {}", event.event, event.data);
+/// }
+/// ```
+pub struct EventSource {
+    /// Owned by `stream` while a connection is open
+    curl: Option<Curl>,
+    stream: Option<ResponseStream>,
+    parser: Parser,
+    events: VecDeque<Event>,
+    retry: Duration,
+    max_retries: Option<u32>,
+    closed: bool,
+}
+
+impl EventSource {
+    /// Connect to an event stream URL
+    pub fn connect(url: &str) -> Result<Self> {
+        let mut curl = Curl::new()?;
+        curl.set_url(url)?;
+        Self::with_curl(curl)
+    }
+
+    /// Connect with a configured handle
+    ///
+    /// Connection errors are returned right away rather than retried.
+    pub fn with_curl(mut curl: Curl) -> Result<Self> {
+        curl.headers_mut().insert("Accept", "text/event-stream")?;
+        curl.headers_mut().insert("Cache-Control", "no-cache")?;
+
+        let mut source = EventSource {
+            curl: Some(curl),
+            stream: None,
+            parser: Parser::new(),
+            events: VecDeque::new(),
+            retry: DEFAULT_RETRY,
+            max_retries: None,
+            closed: false,
+        };
+        source.open()?;
+        Ok(source)
+    }
+
+    /// ID of the last event with one, sent as `Last-Event-ID` on reconnect
+    pub fn last_event_id(&self) -> Option<&str> {
+        Some(self.parser.last_event_id.as_str()).filter(|id| !id.is_empty())
+    }
+
+    /// Delay before reconnecting
+    pub fn retry(&self) -> Duration {
+        self.retry
+    }
+
+    /// Set the delay before reconnecting; the server can change it with a
+    /// `retry:` field
+    pub fn set_retry(&mut self, retry: Duration) {
+        self.retry = retry;
+    }
+
+    /// Give up after this many failed reconnection attempts in a row, or
+    /// never with `None` (the default)
+    pub fn set_max_retries(&mut self, max_retries: Option<u32>) {
+        self.max_retries = max_retries;
+    }
+
+    /// Drop the connection; the iterator ends
+    pub fn close(&mut self) {
+        self.closed = true;
+        self.events.clear();
+        if let Some(stream) = self.stream.take() {
+            self.curl = Some(stream.into_curl());
+        }
+    }
+
+    /// Make one connection attempt
+    ///
+    /// The handle is kept for another attempt unless it failed before the
+    /// transfer could start.
+    fn open(&mut self) -> Result<()> {
+        let mut curl = self.curl.take().expect("event source handle in use");
+        // The server may have reset the ID with an empty `id:` field
+        curl.headers_mut().remove("Last-Event-ID");
+        if !self.parser.last_event_id.is_empty() {
+            let id = self.parser.last_event_id.as_str();
+            if let Err(e) = curl.headers_mut().insert("Last-Event-ID", id) {
+                self.curl = Some(curl);
+                return Err(e);
+            }
+        }
+
+        let mut stream = ResponseStream::attach(curl)?;
+        match stream
+            .wait_for_body()
+            .and_then(|()| check_response(&stream))
+        {
+            Ok(true) => {
+                self.parser.restart();
+                self.stream = Some(stream);
+                Ok(())
+            }
+            Ok(false) => {
+                self.curl = Some(stream.into_curl());
+                self.close();
+                Ok(())
+            }
+            Err(e) => {
+                self.curl = Some(stream.into_curl());
+                Err(e)
+            }
+        }
+    }
+
+    /// Connect again after the connection ended
+    fn reconnect(&mut self) -> Result<()> {
+        let mut failures = 0;
+        loop {
+            self.wait(self.retry)?;
+            match self.open() {
+                Ok(()) => return Ok(()),
+                // Only network errors are worth another attempt
+                Err(e @ CurlError::CurlCode { .. }) if self.curl.is_some() => {
+                    failures += 1;
+                    if self.max_retries.is_some_and(|max| failures > max) {
+                        return Err(e);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Sleep, returning early if the handle's abort handle is aborted
+    fn wait(&self, duration: Duration) -> Result<()> {
+        let curl = self.curl.as_ref().expect("event source handle in use");
+        let deadline = Instant::now() + duration;
+        loop {
+            if curl.should_stop() {
+                return Err(CurlError::Cancelled);
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(());
+            }
+            thread::sleep(left.min(ABORT_CHECK_INTERVAL));
+        }
+    }
+}
+
+/// Whether the response is an event stream to read, `false` for a 204
+fn check_response(stream: &ResponseStream) -> Result<bool> {
+    let status = stream.status()?;
+    // The server's way of saying "stop reconnecting"
+    if status == 204 {
+        return Ok(false);
+    }
+    if status != 200 {
+        return Err(CurlError::HttpStatus {
+            status,
+            url: stream.url()?,
+        });
+    }
+
+    let content_type = stream.header("Content-Type").unwrap_or_default();
+    let mime_type = content_type.split(';').next().unwrap_or_default().trim();
+    if !mime_type.eq_ignore_ascii_case("text/event-stream") {
+        return Err(CurlError::EventStreamError(format!(
+            "expected Content-Type text/event-stream, got {:?}",
+            content_type
+        )));
+    }
+    Ok(true)
+}
+
+impl Iterator for EventSource {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.closed {
+                return None;
+            }
+            let stream = self.stream.as_mut()?;
+            match stream.next() {
+                Some(Ok(chunk)) => {
+                    self.parser.feed(&chunk, &mut self.events);
+                    if let Some(retry) = self.parser.retry.take() {
+                        self.retry = retry;
+                    }
+                }
+                Some(Err(CurlError::Cancelled)) => {
+                    self.close();
+                    return Some(Err(CurlError::Cancelled));
+                }
+                // The connection ended or dropped
+                _ => {
+                    let stream = self.stream.take().expect("stream checked above");
+                    self.curl = Some(stream.into_curl());
+                    if let Err(e) = self.reconnect() {
+                        self.close();
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&[u8]]) -> (Vec<Event>, Parser) {
+        let mut parser = Parser::new();
+        let mut events = VecDeque::new();
+        for chunk in chunks {
+            parser.feed(chunk, &mut events);
+        }
+        (events.into(), parser)
+    }
+
+    #[test]
+    fn test_parser_splits_events_across_chunks() {
+        let (events, parser) = parse(&[
+            b"\xef\xbb\xbfdata: one\r",
+            b"\ndata:two\r\r: comment\nevent: update\nid: 7\nda",
+            b"ta: three\n\nretry: 1500\nid\nevent: ignored\n\ndata: cut off",
+        ]);
+        assert_eq!(
+            events,
+            vec![
+                Event {
+                    event: "message".to_string(),
+                    data: "one\ntwo".to_string(),
+                    id: None,
+                },
+                Event {
+                    event: "update".to_string(),
+                    data: "three".to_string(),
+                    id: Some("7".to_string()),
+                },
+            ]
+        );
+        assert_eq!(parser.retry, Some(Duration::from_millis(1500)));
+        // An empty `id:` resets the last event ID
+        assert_eq!(parser.last_event_id, "");
+    }
+}
@@ -0,0 +1,428 @@
+//! Streaming response bodies: push-style sinks and a pull-style chunk iterator
+
+use crate::curl::Curl;
+use crate::error::{check_code, check_multi_code, CurlError, Result};
+use crate::header::HeaderMap;
+use crate::response::parse_header_lines;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+/// Write target for `Curl::perform_into`, remembering the sink's first error
+pub(crate) struct WriteSink<'a> {
+    sink: &'a mut dyn Write,
+    error: Option<io::Error>,
+    /// Handle whose redirect bodies are held back, when redirects are
+    /// followed by hand
+    redirects_of: *mut curl_sys::CURL,
+    /// Whether the current response has been checked for a redirect yet
+    checked: bool,
+    /// Body of the current response while it is a redirect
+    held: Option<Vec<u8>>,
+}
+
+impl<'a> WriteSink<'a> {
+    pub(crate) fn new(sink: &'a mut dyn Write) -> Self {
+        WriteSink {
+            sink,
+            error: None,
+            redirects_of: ptr::null_mut(),
+            checked: false,
+            held: None,
+        }
+    }
+
+    /// Keep the bodies of redirect responses on `handle` out of the sink
+    ///
+    /// Once the redirect is either followed or not, call
+    /// [`next_hop`](Self::next_hop) or [`release_held`](Self::release_held).
+    pub(crate) fn hold_redirects(&mut self, handle: *mut curl_sys::CURL) {
+        self.redirects_of = handle;
+    }
+
+    /// Drop the held body of a followed redirect
+    pub(crate) fn next_hop(&mut self) {
+        self.checked = false;
+        self.held = None;
+    }
+
+    /// Write the held body of a redirect that wasn't followed
+    pub(crate) fn release_held(&mut self) -> io::Result<()> {
+        match self.held.take() {
+            Some(body) => self.sink.write_all(&body),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
+
+/// Whether the response being received on `handle` redirects elsewhere
+fn is_redirect(handle: *mut curl_sys::CURL) -> bool {
+    let mut code: std::os::raw::c_long = 0;
+    let mut location: *mut c_char = ptr::null_mut();
+    unsafe {
+        curl_sys::curl_easy_getinfo(handle, curl_sys::CURLINFO_RESPONSE_CODE, &mut code);
+        curl_sys::curl_easy_getinfo(handle, curl_sys::CURLINFO_REDIRECT_URL, &mut location);
+    }
+    (300..400).contains(&code) && !location.is_null()
+}
+
+/// Write callback forwarding every chunk to a [`WriteSink`]
+///
+/// Returning anything but `total_size` makes libcurl abort the transfer with
+/// `CURLE_WRITE_ERROR`; the caller reports the stored sink error instead.
+pub(crate) extern "C" fn sink_write_func(
+    ptr: *mut c_char,
+    size: usize,
+    nmemb: usize,
+    userdata: *mut c_void,
+) -> usize {
+    let total_size = size * nmemb;
+    // No sink attached: discard the data
+    if userdata.is_null() {
+        return total_size;
+    }
+    if total_size == 0 || ptr.is_null() {
+        return 0;
+    }
+
+    let sink = unsafe { &mut *(userdata as *mut WriteSink) };
+    let data = unsafe { std::slice::from_raw_parts(ptr as *const u8, total_size) };
+
+    // Headers are complete by the first body chunk, so the status is known
+    if !sink.checked {
+        sink.checked = true;
+        if !sink.redirects_of.is_null() && is_redirect(sink.redirects_of) {
+            sink.held = Some(Vec::new());
+        }
+    }
+    if let Some(held) = &mut sink.held {
+        held.extend_from_slice(data);
+        return total_size;
+    }
+
+    match sink.sink.write_all(data) {
+        Ok(()) => total_size,
+        Err(e) => {
+            sink.error = Some(e);
+            0
+        }
+    }
+}
+
+/// Pause the transfer once this much body data is waiting to be consumed
+const HIGH_WATER_MARK: usize = 1024 * 1024;
+
+/// How long to wait for socket activity before driving the transfer again
+const POLL_TIMEOUT_MS: c_int = 1000;
+
+/// Chunks received by the write callback but not yet handed out
+#[derive(Default)]
+struct StreamState {
+    chunks: VecDeque<Vec<u8>>,
+    buffered: usize,
+    paused: bool,
+    header_lines: Vec<String>,
+}
+
+extern "C" fn stream_write_func(
+    ptr: *mut c_char,
+    size: usize,
+    nmemb: usize,
+    userdata: *mut c_void,
+) -> usize {
+    let total_size = size * nmemb;
+    let state = unsafe { &mut *(userdata as *mut StreamState) };
+
+    // Let the consumer catch up; libcurl redelivers this data after unpausing
+    if state.buffered >= HIGH_WATER_MARK {
+        state.paused = true;
+        return curl_sys::CURL_WRITEFUNC_PAUSE;
+    }
+
+    let data = unsafe { std::slice::from_raw_parts(ptr as *const u8, total_size) };
+    state.chunks.push_back(data.to_vec());
+    state.buffered += total_size;
+    total_size
+}
+
+/// Response body delivered chunk by chunk while the transfer runs
+///
+/// Created by [`Curl::perform_stream`] or [`crate::Request::send_stream`].
+/// Each call to `next` drives the transfer until more data arrives, so only
+/// a bounded amount of the body is buffered at any time.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::Request;
+/// use std::io::Write;
+///
+/// let stream = Request::get("https://httpbin.org/stream/100").send_stream().unwrap();
+/// let mut out = std::io::stdout();
+/// for chunk in stream {
+///     out.write_all(&chunk.unwrap()).unwrap();
+/// }
+/// ```
+pub struct ResponseStream {
+    curl: Option<Curl>,
+    multi: *mut curl_sys::CURLM,
+    state: Box<StreamState>,
+    finished: bool,
+    /// Still looking for the final response of a hand-followed redirect chain
+    following: bool,
+}
+
+impl ResponseStream {
+    pub(crate) fn start(curl: Curl) -> Result<Self> {
+        let mut stream = Self::attach(curl)?;
+        stream.wait_for_body()?;
+        Ok(stream)
+    }
+
+    /// Add the handle to a multi handle of its own, ready to run
+    pub(crate) fn attach(mut curl: Curl) -> Result<Self> {
+        let mut state = Box::<StreamState>::default();
+        let handle = curl.raw_handle();
+
+        unsafe {
+            let code = curl_sys::curl_easy_setopt(
+                handle,
+                curl_sys::CURLOPT_WRITEFUNCTION,
+                stream_write_func as *const c_void,
+            );
+            check_code(code)?;
+            let code = curl_sys::curl_easy_setopt(
+                handle,
+                curl_sys::CURLOPT_WRITEDATA,
+                &mut *state as *mut StreamState as *mut c_void,
+            );
+            check_code(code)?;
+        }
+        curl.set_header_sink(&mut state.header_lines);
+        curl.prepare_transfer()?;
+
+        let multi = unsafe { curl_sys::curl_multi_init() };
+        if multi.is_null() {
+            curl.set_header_sink(ptr::null_mut());
+            return Err(CurlError::InitError);
+        }
+
+        let stream = ResponseStream {
+            curl: Some(curl),
+            multi,
+            state,
+            finished: false,
+            following: true,
+        };
+        check_multi_code(unsafe { curl_sys::curl_multi_add_handle(multi, handle) })?;
+        Ok(stream)
+    }
+
+    /// Run until the body starts (or the transfer ends) so status and
+    /// headers are known before the caller sees the stream
+    ///
+    /// On failure the handle can still be taken back with
+    /// [`into_curl`](Self::into_curl).
+    pub(crate) fn wait_for_body(&mut self) -> Result<()> {
+        loop {
+            while self.state.chunks.is_empty() && !self.finished {
+                self.step()?;
+            }
+            if self.finished || !self.follow_redirect()? {
+                break;
+            }
+        }
+        self.following = false;
+        Ok(())
+    }
+
+    /// Restart the transfer on the next hop if the redirect policy follows
+    /// the current response by hand
+    fn follow_redirect(&mut self) -> Result<bool> {
+        let curl = self.curl.as_mut().expect("stream handle already taken");
+        if !curl.follow_redirect()? {
+            return Ok(false);
+        }
+
+        let handle = curl.raw_handle();
+        check_multi_code(unsafe { curl_sys::curl_multi_remove_handle(self.multi, handle) })?;
+        // The redirect body is never handed out
+        self.state.chunks.clear();
+        self.state.buffered = 0;
+        self.state.paused = false;
+        curl.prepare_transfer()?;
+        check_multi_code(unsafe { curl_sys::curl_multi_add_handle(self.multi, handle) })?;
+        Ok(true)
+    }
+
+    fn curl(&self) -> &Curl {
+        self.curl.as_ref().expect("stream handle already taken")
+    }
+
+    /// HTTP status code of the response
+    pub fn status(&self) -> Result<u16> {
+        Ok(u16::try_from(self.curl().response_code()?).unwrap_or_default())
+    }
+
+    /// Response headers received so far (final response only)
+    pub fn headers(&self) -> HeaderMap {
+        parse_header_lines(&self.state.header_lines)
+    }
+
+    /// Get the first value of a header (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<String> {
+        self.headers().get(name).map(str::to_string)
+    }
+
+    /// Final URL after redirects
+    pub fn url(&self) -> Result<String> {
+        self.curl().effective_url()
+    }
+
+    /// Stop streaming and get the handle back for reuse
+    ///
+    /// An unfinished transfer is abandoned.
+    pub fn into_curl(mut self) -> Curl {
+        self.detach();
+        self.curl.take().expect("stream handle already taken")
+    }
+
+    /// Drive the transfer once, waiting for socket activity if nothing is ready
+    fn step(&mut self) -> Result<()> {
+        let handle = self.curl().raw_handle();
+
+        if self.state.paused {
+            self.state.paused = false;
+            check_code(unsafe { curl_sys::curl_easy_pause(handle, 0) })?;
+        }
+
+        let mut running: c_int = 0;
+        check_multi_code(unsafe { curl_sys::curl_multi_perform(self.multi, &mut running) })?;
+
+        let mut queued: c_int = 0;
+        loop {
+            let msg = unsafe { curl_sys::curl_multi_info_read(self.multi, &mut queued) };
+            if msg.is_null() {
+                break;
+            }
+            let msg = unsafe { &*msg };
+            if msg.msg == curl_sys::CURLMSG_DONE && msg.easy_handle == handle {
+                // `data` is a union whose `result` member holds the CURLcode
+                let code = msg.data as usize as curl_sys::CURLcode;
+                if code == curl_sys::CURLE_OK && self.following && self.follow_redirect()? {
+                    return Ok(());
+                }
+                self.finished = true;
+                self.detach();
+                let curl = self.curl.as_mut().expect("stream handle already taken");
+                return curl.transfer_result(code);
+            }
+        }
+
+        if running > 0 && self.state.chunks.is_empty() && !self.state.paused {
+            check_multi_code(unsafe {
+                curl_sys::curl_multi_poll(
+                    self.multi,
+                    ptr::null_mut(),
+                    0,
+                    POLL_TIMEOUT_MS,
+                    ptr::null_mut(),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Remove the handle from the multi stack and unhook the callbacks
+    fn detach(&mut self) {
+        if self.multi.is_null() {
+            return;
+        }
+        if let Some(curl) = self.curl.as_mut() {
+            let handle = curl.raw_handle();
+            unsafe {
+                curl_sys::curl_multi_remove_handle(self.multi, handle);
+                curl_sys::curl_easy_setopt(
+                    handle,
+                    curl_sys::CURLOPT_WRITEFUNCTION,
+                    sink_write_func as *const c_void,
+                );
+                curl_sys::curl_easy_setopt(
+                    handle,
+                    curl_sys::CURLOPT_WRITEDATA,
+                    ptr::null_mut::<c_void>(),
+                );
+            }
+            curl.set_header_sink(ptr::null_mut());
+            // Nothing to report from here; the transfer itself has ended
+            let _ = curl.finish_redirects();
+        }
+        unsafe {
+            curl_sys::curl_multi_cleanup(self.multi);
+        }
+        self.multi = ptr::null_mut();
+    }
+}
+
+impl Iterator for ResponseStream {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(chunk) = self.state.chunks.pop_front() {
+                self.state.buffered -= chunk.len();
+                return Some(Ok(chunk));
+            }
+            if self.finished {
+                return None;
+            }
+            if let Err(e) = self.step() {
+                self.finished = true;
+                self.detach();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl Drop for ResponseStream {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+// The multi handle is only ever used together with the owned easy handle
+unsafe impl Send for ResponseStream {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sink_write_func_reports_error() {
+        let mut writer = FailingWriter;
+        let mut sink = WriteSink::new(&mut writer);
+        let data = b"chunk";
+        let userdata = &mut sink as *mut WriteSink as *mut c_void;
+
+        let n = sink_write_func(data.as_ptr() as *mut c_char, 1, data.len(), userdata);
+        assert_eq!(n, 0, "A failing sink must abort the transfer");
+        assert!(sink.take_error().is_some());
+    }
+}
@@ -0,0 +1,143 @@
+//! Easy handles driven by a multi handle, collecting whole responses
+
+use crate::curl::Curl;
+use crate::error::{check_code, Result};
+use crate::response::Response;
+use crate::stream::sink_write_func;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+
+/// Body and headers received so far, boxed so the callback pointers stay
+/// valid while the `Transfer` moves
+#[derive(Default)]
+struct Received {
+    body: Vec<u8>,
+    header_lines: Vec<String>,
+}
+
+extern "C" fn collect_write_func(
+    ptr: *const c_char,
+    size: usize,
+    nmemb: usize,
+    userdata: *mut c_void,
+) -> usize {
+    let total_size = size * nmemb;
+    let received = unsafe { &mut *(userdata as *mut Received) };
+    let data = unsafe { std::slice::from_raw_parts(ptr as *const u8, total_size) };
+    received.body.extend_from_slice(data);
+    total_size
+}
+
+/// A request whose handle is added to a multi handle by the caller
+///
+/// The caller adds [`raw_handle`](Self::raw_handle) to its multi handle,
+/// and passes the result code of `CURLMSG_DONE` to
+/// [`complete`](Self::complete) after removing it again.
+pub(crate) struct Transfer {
+    /// Only `None` once taken back by `into_curl`
+    curl: Option<Curl>,
+    received: Box<Received>,
+}
+
+impl Transfer {
+    /// Point the handle's callbacks at a fresh buffer and prepare the first
+    /// hop
+    pub(crate) fn new(mut curl: Curl) -> Result<Self> {
+        let mut received = Box::<Received>::default();
+        let handle = curl.raw_handle();
+        unsafe {
+            check_code(curl_sys::curl_easy_setopt(
+                handle,
+                curl_sys::CURLOPT_WRITEFUNCTION,
+                collect_write_func as *const c_void,
+            ))?;
+            check_code(curl_sys::curl_easy_setopt(
+                handle,
+                curl_sys::CURLOPT_WRITEDATA,
+                &mut *received as *mut Received as *mut c_void,
+            ))?;
+        }
+        curl.set_header_sink(&mut received.header_lines);
+
+        let mut transfer = Transfer {
+            curl: Some(curl),
+            received,
+        };
+        transfer.curl().prepare_transfer()?;
+        Ok(transfer)
+    }
+
+    fn curl(&mut self) -> &mut Curl {
+        self.curl.as_mut().expect("transfer handle already taken")
+    }
+
+    pub(crate) fn raw_handle(&self) -> *mut curl_sys::CURL {
+        self.curl
+            .as_ref()
+            .expect("transfer handle already taken")
+            .raw_handle()
+    }
+
+    /// Handle the end of the current hop
+    ///
+    /// Returns `true` when a redirect is followed by hand: the handle is set
+    /// up for the next hop and must be added to the multi handle again.
+    pub(crate) fn complete(&mut self, code: curl_sys::CURLcode) -> Result<bool> {
+        let curl = self.curl();
+        curl.transfer_result(code)?;
+        if !curl.follow_redirect()? {
+            return Ok(false);
+        }
+        curl.prepare_transfer()?;
+        // The redirect's body is dropped, while the headers of every hop are
+        // kept for `Response::history`
+        self.received.body.clear();
+        Ok(true)
+    }
+
+    /// The response of a transfer [`complete`](Self::complete) finished
+    pub(crate) fn response(&mut self) -> Result<Response> {
+        let body = std::mem::take(&mut self.received.body);
+        let curl = self.curl.as_mut().expect("transfer handle already taken");
+        curl.finish_redirects()?;
+        Response::from_transfer(curl, body, &self.received.header_lines)
+    }
+
+    /// Get the handle back, with the request a redirect chain changed
+    /// restored
+    ///
+    /// The handle must no longer be in a multi handle.
+    pub(crate) fn into_curl(mut self) -> Curl {
+        self.detach();
+        self.curl.take().expect("transfer handle already taken")
+    }
+
+    /// Unhook the callbacks, as the handle may outlive the buffer they point
+    /// at
+    fn detach(&mut self) {
+        let Some(curl) = self.curl.as_mut() else {
+            return;
+        };
+        unsafe {
+            curl_sys::curl_easy_setopt(
+                curl.raw_handle(),
+                curl_sys::CURLOPT_WRITEFUNCTION,
+                sink_write_func as *const c_void,
+            );
+            curl_sys::curl_easy_setopt(
+                curl.raw_handle(),
+                curl_sys::CURLOPT_WRITEDATA,
+                ptr::null_mut::<c_void>(),
+            );
+        }
+        curl.set_header_sink(ptr::null_mut());
+        // Nothing to report from here; the transfer itself has ended
+        let _ = curl.finish_redirects();
+    }
+}
+
+impl Drop for Transfer {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
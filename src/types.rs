@@ -28,6 +28,9 @@ pub enum CurlOpt {
     CookieJar,
     HttpVersion,
     CustomRequest,
+    HttpGet,
+    Post,
+    NoBody,
     SslCert,
     SslKey,
     SslKeyType,
@@ -40,8 +43,24 @@ pub enum CurlOpt {
     ProxySslVerifyPeer,
     ProxySslVerifyHost,
     SslCipherList,
+    Tls13Ciphers,
     SslCurves,
+    SslSignatureAlgorithms,
+    SslEnableAlpn,
+    SslVersion,
+    SslSigHashAlgs,
+    Http2PseudoHeadersOrder,
+    Http2Settings,
+    Http2WindowUpdate,
+    Http2Streams,
     AcceptEncoding,
+    SuppressConnectHeaders,
+    PostRedir,
+    TimeoutMs,
+    ConnectTimeoutMs,
+    MaxConnects,
+    ConnectOnly,
+    WsOptions,
 }
 
 impl CurlOpt {
@@ -71,6 +90,9 @@ impl CurlOpt {
             CurlOpt::CookieJar => CURLOPT_COOKIEJAR,
             CurlOpt::HttpVersion => CURLOPT_HTTP_VERSION,
             CurlOpt::CustomRequest => CURLOPT_CUSTOMREQUEST,
+            CurlOpt::HttpGet => CURLOPT_HTTPGET,
+            CurlOpt::Post => CURLOPT_POST,
+            CurlOpt::NoBody => CURLOPT_NOBODY,
             CurlOpt::SslCert => CURLOPT_SSLCERT,
             CurlOpt::SslKey => CURLOPT_SSLKEY,
             CurlOpt::SslKeyType => CURLOPT_SSLKEYTYPE,
@@ -83,8 +105,24 @@ impl CurlOpt {
             CurlOpt::ProxySslVerifyPeer => CURLOPT_PROXY_SSL_VERIFYPEER,
             CurlOpt::ProxySslVerifyHost => CURLOPT_PROXY_SSL_VERIFYHOST,
             CurlOpt::SslCipherList => CURLOPT_SSL_CIPHER_LIST,
-            CurlOpt::SslCurves => CURLOPT_SSLVERSION, // Use CURLOPT_SSLVERSION as placeholder
+            CurlOpt::Tls13Ciphers => crate::ffi::CURLOPT_TLS13_CIPHERS,
+            CurlOpt::SslCurves => crate::ffi::CURLOPT_SSL_EC_CURVES,
+            CurlOpt::SslSignatureAlgorithms => crate::ffi::CURLOPT_SSL_SIGNATURE_ALGORITHMS,
+            CurlOpt::SslEnableAlpn => crate::ffi::CURLOPT_SSL_ENABLE_ALPN,
+            CurlOpt::SslVersion => CURLOPT_SSLVERSION,
+            CurlOpt::SslSigHashAlgs => crate::ffi::CURLOPT_SSL_SIG_HASH_ALGS,
+            CurlOpt::Http2PseudoHeadersOrder => crate::ffi::CURLOPT_HTTP2_PSEUDO_HEADERS_ORDER,
+            CurlOpt::Http2Settings => crate::ffi::CURLOPT_HTTP2_SETTINGS,
+            CurlOpt::Http2WindowUpdate => crate::ffi::CURLOPT_HTTP2_WINDOW_UPDATE,
+            CurlOpt::Http2Streams => crate::ffi::CURLOPT_HTTP2_STREAMS,
             CurlOpt::AcceptEncoding => CURLOPT_ACCEPT_ENCODING,
+            CurlOpt::SuppressConnectHeaders => crate::ffi::CURLOPT_SUPPRESS_CONNECT_HEADERS,
+            CurlOpt::PostRedir => CURLOPT_POSTREDIR,
+            CurlOpt::TimeoutMs => CURLOPT_TIMEOUT_MS,
+            CurlOpt::ConnectTimeoutMs => CURLOPT_CONNECTTIMEOUT_MS,
+            CurlOpt::MaxConnects => CURLOPT_MAXCONNECTS,
+            CurlOpt::ConnectOnly => CURLOPT_CONNECT_ONLY,
+            CurlOpt::WsOptions => crate::ffi::CURLOPT_WS_OPTIONS,
         }
     }
 }
@@ -106,6 +144,9 @@ pub enum CurlInfo {
     SpeedUpload,
     ContentType,
     EffectiveUrl,
+    AppConnectTime,
+    HttpVersion,
+    RedirectUrl,
 }
 
 impl CurlInfo {
@@ -126,6 +167,9 @@ impl CurlInfo {
             CurlInfo::SpeedUpload => CURLINFO_SPEED_UPLOAD,
             CurlInfo::ContentType => CURLINFO_CONTENT_TYPE,
             CurlInfo::EffectiveUrl => CURLINFO_EFFECTIVE_URL,
+            CurlInfo::AppConnectTime => CURLINFO_APPCONNECT_TIME,
+            CurlInfo::HttpVersion => crate::ffi::CURLINFO_HTTP_VERSION,
+            CurlInfo::RedirectUrl => CURLINFO_REDIRECT_URL,
         }
     }
 }
@@ -159,6 +203,204 @@ impl HttpVersion {
             HttpVersion::V3 => CURL_HTTP_VERSION_3 as i64,
         }
     }
+
+    /// Convert from the negotiated version reported by `CURLINFO_HTTP_VERSION`
+    ///
+    /// Returns `None` when no HTTP transfer has happened yet.
+    pub fn from_curl(value: i64) -> Option<Self> {
+        match value {
+            v if v == CURL_HTTP_VERSION_1_0 as i64 => Some(HttpVersion::V1_0),
+            v if v == CURL_HTTP_VERSION_1_1 as i64 => Some(HttpVersion::V1_1),
+            v if v == CURL_HTTP_VERSION_2_0 as i64 => Some(HttpVersion::V2),
+            v if v == CURL_HTTP_VERSION_3 as i64 => Some(HttpVersion::V3),
+            _ => None,
+        }
+    }
+}
+
+/// TLS protocol version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    /// TLS 1.0
+    V1_0,
+    /// TLS 1.1
+    V1_1,
+    /// TLS 1.2
+    V1_2,
+    /// TLS 1.3
+    V1_3,
+}
+
+impl TlsVersion {
+    /// Convert to curl constant, as a minimum for `CURLOPT_SSLVERSION`
+    pub fn to_curl(self) -> i64 {
+        match self {
+            TlsVersion::V1_0 => CURL_SSLVERSION_TLSv1_0 as i64,
+            TlsVersion::V1_1 => CURL_SSLVERSION_TLSv1_1 as i64,
+            TlsVersion::V1_2 => CURL_SSLVERSION_TLSv1_2 as i64,
+            TlsVersion::V1_3 => CURL_SSLVERSION_TLSv1_3 as i64,
+        }
+    }
+
+    /// Convert to curl constant, as a maximum for `CURLOPT_SSLVERSION`
+    pub fn to_curl_max(self) -> i64 {
+        self.to_curl() << 16
+    }
+}
+
+impl std::fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TlsVersion::V1_0 => "TLS 1.0",
+            TlsVersion::V1_1 => "TLS 1.1",
+            TlsVersion::V1_2 => "TLS 1.2",
+            TlsVersion::V1_3 => "TLS 1.3",
+        })
+    }
+}
+
+/// HTTP request method
+///
+/// Anything that is not one of the standard verbs is sent verbatim via
+/// `CURLOPT_CUSTOMREQUEST`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+    /// Non-standard verb such as `PURGE`
+    Custom(String),
+}
+
+impl Method {
+    /// Method name as sent on the request line
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Head => "HEAD",
+            Method::Options => "OPTIONS",
+            Method::Custom(name) => name,
+        }
+    }
+}
+
+impl From<&str> for Method {
+    fn from(name: &str) -> Self {
+        match name.to_ascii_uppercase().as_str() {
+            "GET" => Method::Get,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "PATCH" => Method::Patch,
+            "DELETE" => Method::Delete,
+            "HEAD" => Method::Head,
+            "OPTIONS" => Method::Options,
+            _ => Method::Custom(name.to_string()),
+        }
+    }
+}
+
+impl From<String> for Method {
+    fn from(name: String) -> Self {
+        Method::from(name.as_str())
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A PRIORITY frame, or the priority carried by a HEADERS frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Http2Priority {
+    pub stream_id: u32,
+    pub exclusive: bool,
+    pub depends_on: u32,
+    /// 1 to 256, one more than the byte sent on the wire
+    pub weight: u16,
+}
+
+/// HTTP/2 connection behaviour of a browser, which servers fingerprint
+/// alongside the TLS handshake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http2Fingerprint {
+    /// SETTINGS frame entries as identifier and value, in the order sent
+    pub settings: &'static [(u16, u32)],
+    /// Connection WINDOW_UPDATE increment sent after SETTINGS, 0 for none
+    pub window_update: u32,
+    /// PRIORITY frames sent when the connection opens
+    pub priority_frames: &'static [Http2Priority],
+    /// Order of the pseudo-headers by first letter, like `"masp"` for
+    /// `:method`, `:authority`, `:scheme`, `:path`
+    pub pseudo_header_order: &'static str,
+}
+
+impl Http2Fingerprint {
+    /// SETTINGS entries as `id:value` joined with `;`, the format of both
+    /// the Akamai fingerprint and `CURLOPT_HTTP2_SETTINGS`
+    pub fn settings_string(&self) -> String {
+        self.settings
+            .iter()
+            .map(|(id, value)| format!("{}:{}", id, value))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// PRIORITY frames as `stream:exclusive:depends_on:weight` joined with
+    /// `,`, the format of both the Akamai fingerprint and
+    /// `CURLOPT_HTTP2_STREAMS`
+    pub fn priority_string(&self) -> String {
+        self.priority_frames
+            .iter()
+            .map(|frame| {
+                format!(
+                    "{}:{}:{}:{}",
+                    frame.stream_id, frame.exclusive as u8, frame.depends_on, frame.weight
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Akamai HTTP/2 fingerprint, `SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`
+    ///
+    /// # Examples
+    /// ```
+    /// use hyprcurl::Browser;
+    ///
+    /// assert_eq!(
+    ///     Browser::ChromeLatest.http2_fingerprint().akamai(),
+    ///     "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+    /// );
+    /// ```
+    pub fn akamai(&self) -> String {
+        let window_update = match self.window_update {
+            0 => "00".to_string(),
+            increment => increment.to_string(),
+        };
+        let priority = match self.priority_string() {
+            frames if frames.is_empty() => "0".to_string(),
+            frames => frames,
+        };
+        let pseudo_headers: Vec<String> =
+            self.pseudo_header_order.chars().map(String::from).collect();
+        format!(
+            "{}|{}|{}|{}",
+            self.settings_string(),
+            window_update,
+            priority,
+            pseudo_headers.join(",")
+        )
+    }
 }
 
 /// Browser impersonation types
@@ -372,6 +614,116 @@ impl Browser {
             Browser::Safari { .. } | Browser::SafariLatest => "X25519:P-256:P-384:P-521",
         }
     }
+
+    /// Get TLS signature algorithms for the browser, in ClientHello order
+    ///
+    /// OpenSSL has no TLS 1.3 style name for ECDSA with SHA-1, so that one is
+    /// spelled `ECDSA+SHA1`.
+    pub fn tls_sigalgs(&self) -> &'static str {
+        match self {
+            Browser::Chrome { .. }
+            | Browser::ChromeLatest
+            | Browser::Edge { .. }
+            | Browser::EdgeLatest => {
+                "ecdsa_secp256r1_sha256:rsa_pss_rsae_sha256:rsa_pkcs1_sha256:ecdsa_secp384r1_sha384:rsa_pss_rsae_sha384:rsa_pkcs1_sha384:rsa_pss_rsae_sha512:rsa_pkcs1_sha512"
+            }
+            Browser::Firefox { .. }
+            | Browser::FirefoxLatest
+            | Browser::Tor { .. }
+            | Browser::TorLatest => {
+                "ecdsa_secp256r1_sha256:ecdsa_secp384r1_sha384:ecdsa_secp521r1_sha512:rsa_pss_rsae_sha256:rsa_pss_rsae_sha384:rsa_pss_rsae_sha512:rsa_pkcs1_sha256:rsa_pkcs1_sha384:rsa_pkcs1_sha512:ECDSA+SHA1:rsa_pkcs1_sha1"
+            }
+            Browser::Safari { .. } | Browser::SafariLatest => {
+                "ecdsa_secp256r1_sha256:rsa_pss_rsae_sha256:rsa_pkcs1_sha256:ecdsa_secp384r1_sha384:ECDSA+SHA1:rsa_pss_rsae_sha384:rsa_pkcs1_sha384:rsa_pss_rsae_sha512:rsa_pkcs1_sha512:rsa_pkcs1_sha1"
+            }
+        }
+    }
+
+    /// Get the browser's HTTP/2 SETTINGS, WINDOW_UPDATE, PRIORITY frames and
+    /// pseudo-header order
+    pub fn http2_fingerprint(&self) -> Http2Fingerprint {
+        match self {
+            Browser::Chrome { .. }
+            | Browser::ChromeLatest
+            | Browser::Edge { .. }
+            | Browser::EdgeLatest => Http2Fingerprint {
+                settings: &[(1, 65536), (2, 0), (4, 6291456), (6, 262144)],
+                window_update: 15663105,
+                priority_frames: &[],
+                pseudo_header_order: "masp",
+            },
+            Browser::Firefox { .. }
+            | Browser::FirefoxLatest
+            | Browser::Tor { .. }
+            | Browser::TorLatest => Http2Fingerprint {
+                settings: &[(1, 65536), (4, 131072), (5, 16384)],
+                window_update: 12517377,
+                // Firefox's dependency tree of placeholder streams
+                priority_frames: &[
+                    Http2Priority {
+                        stream_id: 3,
+                        exclusive: false,
+                        depends_on: 0,
+                        weight: 201,
+                    },
+                    Http2Priority {
+                        stream_id: 5,
+                        exclusive: false,
+                        depends_on: 0,
+                        weight: 101,
+                    },
+                    Http2Priority {
+                        stream_id: 7,
+                        exclusive: false,
+                        depends_on: 0,
+                        weight: 1,
+                    },
+                    Http2Priority {
+                        stream_id: 9,
+                        exclusive: false,
+                        depends_on: 7,
+                        weight: 1,
+                    },
+                    Http2Priority {
+                        stream_id: 11,
+                        exclusive: false,
+                        depends_on: 3,
+                        weight: 1,
+                    },
+                    Http2Priority {
+                        stream_id: 13,
+                        exclusive: false,
+                        depends_on: 0,
+                        weight: 241,
+                    },
+                ],
+                pseudo_header_order: "mpas",
+            },
+            Browser::Safari { .. } | Browser::SafariLatest => Http2Fingerprint {
+                settings: &[(2, 0), (3, 100), (4, 2097152), (9, 1)],
+                window_update: 10420225,
+                priority_frames: &[],
+                pseudo_header_order: "msap",
+            },
+        }
+    }
+
+    /// Get ALPN protocols the browser offers, in preference order
+    ///
+    /// Impersonation speaks HTTP/2 when this lists `h2`, and HTTP/1.1 only
+    /// otherwise, which is what libcurl then offers over ALPN.
+    pub fn alpn(&self) -> &'static [&'static str] {
+        &["h2", "http/1.1"]
+    }
+
+    /// Get the lowest and highest TLS versions the browser offers
+    pub fn tls_versions(&self) -> (TlsVersion, TlsVersion) {
+        match self {
+            // Safari still lists TLS 1.0 and 1.1 in supported_versions
+            Browser::Safari { .. } | Browser::SafariLatest => (TlsVersion::V1_0, TlsVersion::V1_3),
+            _ => (TlsVersion::V1_2, TlsVersion::V1_3),
+        }
+    }
 }
 
 /// WebSocket frame flags
@@ -384,5 +736,28 @@ impl WsFlags {
     pub const CONT: WsFlags = WsFlags(1 << 2);
     pub const CLOSE: WsFlags = WsFlags(1 << 3);
     pub const PING: WsFlags = WsFlags(1 << 4);
-    pub const PONG: WsFlags = WsFlags(1 << 5);
+    /// `curl_ws_send` only: `fragsize` is the size of the whole frame
+    pub const OFFSET: WsFlags = WsFlags(1 << 5);
+    pub const PONG: WsFlags = WsFlags(1 << 6);
+
+    /// Whether every bit of `other` is set
+    pub fn contains(self, other: WsFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// Status code of a WebSocket close frame (RFC 6455, section 7.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CloseCode(pub u16);
+
+impl CloseCode {
+    pub const NORMAL: CloseCode = CloseCode(1000);
+    pub const GOING_AWAY: CloseCode = CloseCode(1001);
+    pub const PROTOCOL_ERROR: CloseCode = CloseCode(1002);
+    pub const UNSUPPORTED_DATA: CloseCode = CloseCode(1003);
+    pub const INVALID_DATA: CloseCode = CloseCode(1007);
+    pub const POLICY_VIOLATION: CloseCode = CloseCode(1008);
+    pub const MESSAGE_TOO_BIG: CloseCode = CloseCode(1009);
+    pub const MANDATORY_EXTENSION: CloseCode = CloseCode(1010);
+    pub const INTERNAL_ERROR: CloseCode = CloseCode(1011);
 }
@@ -0,0 +1,215 @@
+//! URL manipulation and percent-encoding on top of libcurl's `curl_url` API
+
+use crate::error::{CurlError, Result};
+use crate::ffi;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_uint};
+use std::ptr;
+
+/// Parsed URL owned by a `CURLU` handle
+pub(crate) struct CurlUrl {
+    raw: *mut ffi::CURLU,
+}
+
+impl CurlUrl {
+    /// Parse an absolute URL; a missing scheme is guessed like the curl tool does
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        crate::init_curl();
+        let raw = unsafe { ffi::curl_url() };
+        if raw.is_null() {
+            return Err(CurlError::InitError);
+        }
+
+        let mut parsed = CurlUrl { raw };
+        parsed.set(
+            ffi::CURLUPART_URL,
+            url,
+            ffi::CURLU_GUESS_SCHEME | ffi::CURLU_NON_SUPPORT_SCHEME,
+        )?;
+        Ok(parsed)
+    }
+
+    /// Set one part of the URL
+    pub(crate) fn set(&mut self, part: ffi::CURLUPart, value: &str, flags: c_uint) -> Result<()> {
+        let c_value = CString::new(value).map_err(|_| CurlError::InvalidUrl(value.to_string()))?;
+        let code = unsafe { ffi::curl_url_set(self.raw, part, c_value.as_ptr(), flags) };
+        check_url_code(code, value)
+    }
+
+    /// Get one part of the URL, `None` when the URL doesn't have it
+    pub(crate) fn get(&self, part: ffi::CURLUPart, flags: c_uint) -> Result<Option<String>> {
+        let mut value: *mut c_char = ptr::null_mut();
+        let code = unsafe { ffi::curl_url_get(self.raw, part, &mut value, flags) };
+        if value.is_null() {
+            // Missing parts come back as part-specific error codes
+            return Ok(None);
+        }
+
+        let result = unsafe { CStr::from_ptr(value).to_string_lossy().into_owned() };
+        unsafe { curl_sys::curl_free(value as *mut _) };
+        check_url_code(code, &result)?;
+        Ok(Some(result))
+    }
+
+    /// Append an already encoded `name=value` pair to the query string
+    pub(crate) fn append_query(&mut self, pair: &str) -> Result<()> {
+        self.set(ffi::CURLUPART_QUERY, pair, ffi::CURLU_APPENDQUERY)
+    }
+
+    /// The full URL as a string
+    pub(crate) fn to_url_string(&self) -> Result<String> {
+        self.get(ffi::CURLUPART_URL, 0)?
+            .ok_or_else(|| CurlError::InvalidUrl(String::new()))
+    }
+}
+
+impl Drop for CurlUrl {
+    fn drop(&mut self) {
+        unsafe { ffi::curl_url_cleanup(self.raw) };
+    }
+}
+
+fn check_url_code(code: ffi::CURLUcode, url: &str) -> Result<()> {
+    if code == ffi::CURLUE_OK {
+        return Ok(());
+    }
+    let message = unsafe {
+        CStr::from_ptr(ffi::curl_url_strerror(code))
+            .to_string_lossy()
+            .into_owned()
+    };
+    Err(CurlError::InvalidUrl(format!("{} ({})", url, message)))
+}
+
+/// Percent-encode a string with `curl_easy_escape`
+///
+/// Everything except unreserved characters (`A-Z a-z 0-9 - . _ ~`) is
+/// encoded, so the result is safe as a query or form key or value.
+pub(crate) fn escape(value: &str) -> Result<String> {
+    crate::init_curl();
+    let len = c_int::try_from(value.len())
+        .map_err(|_| CurlError::InvalidOption("Value too long to escape".to_string()))?;
+    let escaped = unsafe {
+        curl_sys::curl_easy_escape(ptr::null_mut(), value.as_ptr() as *const c_char, len)
+    };
+    if escaped.is_null() {
+        return Err(CurlError::Other("Failed to escape value".to_string()));
+    }
+
+    let result = unsafe { CStr::from_ptr(escaped).to_string_lossy().into_owned() };
+    unsafe { curl_sys::curl_free(escaped as *mut _) };
+    Ok(result)
+}
+
+/// Encode pairs as `application/x-www-form-urlencoded`
+pub(crate) fn encode_pairs<K, V>(pairs: &[(K, V)]) -> Result<String>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let encoded = pairs
+        .iter()
+        .map(|(k, v)| Ok(format!("{}={}", escape(k.as_ref())?, escape(v.as_ref())?)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(encoded.join("&"))
+}
+
+/// Resolve a `Location` header value against the URL it was received from
+pub(crate) fn resolve(base: &str, location: &str) -> Result<String> {
+    let mut url = CurlUrl::parse(base)?;
+    url.set(ffi::CURLUPART_URL, location, ffi::CURLU_NON_SUPPORT_SCHEME)?;
+    url.to_url_string()
+}
+
+/// Host name of an absolute URL, lowercased
+pub(crate) fn host(url: &str) -> Result<Option<String>> {
+    let parsed = CurlUrl::parse(url)?;
+    Ok(parsed
+        .get(ffi::CURLUPART_HOST, 0)?
+        .map(|host| host.to_ascii_lowercase()))
+}
+
+/// Whether two absolute URLs share scheme, host and port
+///
+/// Default ports count, so `https://a.com` and `https://a.com:443/x` match.
+pub(crate) fn same_origin(a: &str, b: &str) -> Result<bool> {
+    let origin = |url: &str| -> Result<_> {
+        let parsed = CurlUrl::parse(url)?;
+        Ok((
+            parsed.get(ffi::CURLUPART_SCHEME, 0)?,
+            parsed
+                .get(ffi::CURLUPART_HOST, 0)?
+                .map(|host| host.to_ascii_lowercase()),
+            parsed.get(ffi::CURLUPART_PORT, ffi::CURLU_DEFAULT_PORT)?,
+        ))
+    };
+    Ok(origin(a)? == origin(b)?)
+}
+
+/// Merge encoded query pairs into `url`, keeping any existing query and fragment
+pub(crate) fn append_query<K, V>(url: &str, pairs: &[(K, V)]) -> Result<String>
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    if pairs.is_empty() {
+        return Ok(url.to_string());
+    }
+
+    let mut parsed = CurlUrl::parse(url)?;
+    for (k, v) in pairs {
+        parsed.append_query(&format!("{}={}", escape(k.as_ref())?, escape(v.as_ref())?))?;
+    }
+    parsed.to_url_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_query_merges() {
+        let url = append_query(
+            "https://example.com/search?lang=en#results",
+            &[("q", "rust & curl"), ("page", "2")],
+        )
+        .unwrap();
+        assert_eq!(
+            url,
+            "https://example.com/search?lang=en&q=rust%20%26%20curl&page=2#results"
+        );
+
+        // libcurl normalizes escapes in the query to lowercase hex
+        let url = append_query("https://example.com", &[("a=b", "c/d")]).unwrap();
+        assert_eq!(url, "https://example.com/?a%3db=c%2fd");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let base = "https://example.com/a/b?x=1";
+        assert_eq!(resolve(base, "/c").unwrap(), "https://example.com/c");
+        assert_eq!(
+            resolve(base, "d?y=2").unwrap(),
+            "https://example.com/a/d?y=2"
+        );
+        assert_eq!(
+            resolve(base, "http://other.org/").unwrap(),
+            "http://other.org/"
+        );
+    }
+
+    #[test]
+    fn test_same_origin() {
+        let base = "https://example.com/a";
+        assert!(same_origin(base, "https://EXAMPLE.com:443/b?c").unwrap());
+        assert!(!same_origin(base, "http://example.com/a").unwrap());
+        assert!(!same_origin(base, "https://example.com:8443/a").unwrap());
+        assert!(!same_origin(base, "https://api.example.com/a").unwrap());
+    }
+
+    #[test]
+    fn test_encode_pairs() {
+        let body = encode_pairs(&[("name", "Jane Doe"), ("note", "50%+")]).unwrap();
+        assert_eq!(body, "name=Jane%20Doe&note=50%25%2B");
+    }
+}
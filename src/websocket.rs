@@ -0,0 +1,565 @@
+//! WebSocket client on libcurl's `CURLOPT_CONNECT_ONLY` WebSocket API
+
+use crate::curl::Curl;
+use crate::error::{check_code, CurlError, Result};
+use crate::ffi;
+use crate::response::Response;
+use crate::types::{CloseCode, CurlOpt, WsFlags};
+use std::os::raw::c_void;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+/// Size of the buffer handed to `curl_ws_recv`
+const RECV_CHUNK: usize = 64 * 1024;
+
+/// Control frames carry at most 125 bytes (RFC 6455, section 5.5)
+const MAX_CONTROL_PAYLOAD: usize = 125;
+
+/// A whole WebSocket message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    /// The peer's close frame, `None` when it carried no status code
+    Close(Option<CloseFrame>),
+}
+
+/// Status code and reason of a close frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseFrame {
+    pub code: CloseCode,
+    pub reason: String,
+}
+
+/// A WebSocket connection
+///
+/// The handshake is made by a [`Curl`] handle, so impersonation, proxies,
+/// headers and cookies configured on it apply to the connection. Messages
+/// split into fragments by the peer are put back together by
+/// [`recv`](Self::recv), and pings are answered with a pong before they are
+/// returned.
+///
+/// # Examples
+/// ```no_run
+/// use hyprcurl::websocket::{Message, WebSocket};
+/// use hyprcurl::CloseCode;
+///
+/// let mut ws = WebSocket::connect("wss://echo.websocket.org").unwrap();
+/// ws.send_text("hello").unwrap();
+/// loop {
+///     match ws.recv().unwrap() {
+///         Message::Text(text) => {
+///             println!("{}", text);
+///             ws.close(CloseCode::NORMAL, "done").unwrap();
+///         }
+///         Message::Close(frame) => {
+///             println!("closed: {:?}", frame);
+///             break;
+///         }
+///         _ => {}
+///     }
+/// }
+/// ```
+pub struct WebSocket {
+    curl: Curl,
+    /// The `101 Switching Protocols` response to the handshake
+    response: Response,
+    buffer: Vec<u8>,
+    frames: Reassembler,
+    read_timeout: Option<Duration>,
+    close_sent: bool,
+}
+
+/// Puts messages back together from the frame parts `curl_ws_recv` hands
+/// out, which may be fragments of a message or pieces of a frame
+#[derive(Default)]
+struct Reassembler {
+    /// Data of a message whose last fragment hasn't arrived
+    message: Vec<u8>,
+    /// Payload of a control frame received in parts
+    control: Vec<u8>,
+    close_received: bool,
+}
+
+impl Reassembler {
+    /// Add part of a frame, with the frame's flags and the number of its
+    /// bytes still to come, and return the message once it is complete
+    fn push(&mut self, flags: WsFlags, data: &[u8], bytes_left: i64) -> Result<Option<Message>> {
+        let is_control = flags.contains(WsFlags::CLOSE)
+            || flags.contains(WsFlags::PING)
+            || flags.contains(WsFlags::PONG);
+
+        // Control frames may arrive between the fragments of a message
+        if is_control {
+            self.control.extend_from_slice(data);
+            if bytes_left > 0 {
+                return Ok(None);
+            }
+            let payload = std::mem::take(&mut self.control);
+            if flags.contains(WsFlags::CLOSE) {
+                self.close_received = true;
+                return parse_close(&payload).map(|frame| Some(Message::Close(frame)));
+            }
+            if flags.contains(WsFlags::PING) {
+                return Ok(Some(Message::Ping(payload)));
+            }
+            return Ok(Some(Message::Pong(payload)));
+        }
+
+        self.message.extend_from_slice(data);
+        // CONT is set on every fragment but the last
+        if bytes_left > 0 || flags.contains(WsFlags::CONT) {
+            return Ok(None);
+        }
+        let payload = std::mem::take(&mut self.message);
+        if flags.contains(WsFlags::TEXT) {
+            let text = String::from_utf8(payload).map_err(|_| {
+                CurlError::WebSocketError("text message is not valid UTF-8".to_string())
+            })?;
+            Ok(Some(Message::Text(text)))
+        } else {
+            Ok(Some(Message::Binary(payload)))
+        }
+    }
+}
+
+impl WebSocket {
+    /// Connect to a `ws://` or `wss://` URL
+    pub fn connect(url: &str) -> Result<Self> {
+        let mut curl = Curl::new()?;
+        curl.set_url(url)?;
+        Self::with_curl(curl)
+    }
+
+    /// Make the handshake with a configured handle
+    ///
+    /// The handle's URL must be set to a `ws://` or `wss://` URL. The handle
+    /// belongs to the connection from then on.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use hyprcurl::{Browser, Curl};
+    /// use hyprcurl::websocket::WebSocket;
+    ///
+    /// let mut curl = Curl::new().unwrap();
+    /// curl.set_url("wss://example.com/socket").unwrap();
+    /// curl.set_browser_impersonation(Browser::ChromeLatest).unwrap();
+    /// curl.add_header("Origin: https://example.com").unwrap();
+    /// let ws = WebSocket::with_curl(curl).unwrap();
+    /// ```
+    pub fn with_curl(mut curl: Curl) -> Result<Self> {
+        // 2 makes libcurl do the upgrade handshake and stop there
+        curl.setopt_long(CurlOpt::ConnectOnly, 2)?;
+        curl.setopt_long(CurlOpt::WsOptions, ffi::CURLWS_NOAUTOPONG)?;
+        let response = match curl.perform_response() {
+            Ok(response) => response,
+            // libcurl fails the handshake itself on an error status
+            Err(CurlError::CurlCode {
+                code: curl_sys::CURLE_HTTP_RETURNED_ERROR,
+                ..
+            }) => return Err(refused(curl.response_code()?)),
+            Err(e) => return Err(e),
+        };
+        if response.status() != 101 {
+            return Err(refused(response.status().into()));
+        }
+
+        Ok(WebSocket {
+            curl,
+            response,
+            buffer: vec![0; RECV_CHUNK],
+            frames: Reassembler::default(),
+            read_timeout: None,
+            close_sent: false,
+        })
+    }
+
+    /// The server's response to the handshake
+    pub fn response(&self) -> &Response {
+        &self.response
+    }
+
+    /// Give up on [`recv`](Self::recv) after waiting this long for data
+    ///
+    /// A timed out `recv` fails with `CURLE_OPERATION_TIMEDOUT`, and any
+    /// part of a message received so far is kept for the next call.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    /// Send a text message
+    pub fn send_text(&mut self, text: &str) -> Result<()> {
+        self.send_frame(text.as_bytes(), WsFlags::TEXT)
+    }
+
+    /// Send a binary message
+    pub fn send_binary(&mut self, data: &[u8]) -> Result<()> {
+        self.send_frame(data, WsFlags::BINARY)
+    }
+
+    /// Send a ping with at most 125 bytes of payload
+    pub fn ping(&mut self, payload: &[u8]) -> Result<()> {
+        check_control_payload(payload)?;
+        self.send_frame(payload, WsFlags::PING)
+    }
+
+    /// Send an unsolicited pong with at most 125 bytes of payload
+    pub fn pong(&mut self, payload: &[u8]) -> Result<()> {
+        check_control_payload(payload)?;
+        self.send_frame(payload, WsFlags::PONG)
+    }
+
+    /// Send a close frame
+    ///
+    /// Nothing can be sent afterwards. Keep calling [`recv`](Self::recv)
+    /// until it returns the peer's [`Message::Close`] to finish the closing
+    /// handshake.
+    pub fn close(&mut self, code: CloseCode, reason: &str) -> Result<()> {
+        let mut payload = code.0.to_be_bytes().to_vec();
+        payload.extend_from_slice(reason.as_bytes());
+        check_control_payload(&payload)?;
+        self.send_frame(&payload, WsFlags::CLOSE)?;
+        self.close_sent = true;
+        Ok(())
+    }
+
+    /// Send one frame with the given flags
+    ///
+    /// Sending a message in fragments is done by setting [`WsFlags::CONT`]
+    /// on every frame but the last, with each frame carrying the message's
+    /// [`WsFlags::TEXT`] or [`WsFlags::BINARY`] flag.
+    pub fn send_frame(&mut self, data: &[u8], flags: WsFlags) -> Result<()> {
+        if self.close_sent {
+            return Err(CurlError::WebSocketError(
+                "close frame already sent".to_string(),
+            ));
+        }
+
+        let mut offset = 0;
+        loop {
+            let mut sent = 0;
+            let code = unsafe {
+                ffi::curl_ws_send(
+                    self.curl.raw_handle(),
+                    data[offset..].as_ptr() as *const c_void,
+                    data.len() - offset,
+                    &mut sent,
+                    0,
+                    flags.0,
+                )
+            };
+            if code == curl_sys::CURLE_AGAIN {
+                self.wait(true, None)?;
+                continue;
+            }
+            check_code(code)?;
+            // A partial send is continued by passing the rest of the data
+            offset += sent;
+            if offset == data.len() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Wait for the next message
+    ///
+    /// Once the peer's close frame has been returned, the connection is
+    /// done and further calls fail.
+    pub fn recv(&mut self) -> Result<Message> {
+        if self.frames.close_received {
+            return Err(CurlError::WebSocketError(
+                "connection already closed".to_string(),
+            ));
+        }
+        let deadline = self.read_timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let mut received = 0;
+            let mut meta: *const ffi::curl_ws_frame = ptr::null();
+            let code = unsafe {
+                ffi::curl_ws_recv(
+                    self.curl.raw_handle(),
+                    self.buffer.as_mut_ptr() as *mut c_void,
+                    self.buffer.len(),
+                    &mut received,
+                    &mut meta,
+                )
+            };
+            match code {
+                curl_sys::CURLE_AGAIN => {
+                    self.wait(false, deadline)?;
+                    continue;
+                }
+                curl_sys::CURLE_GOT_NOTHING => {
+                    return Err(CurlError::WebSocketError(
+                        "connection closed without a close frame".to_string(),
+                    ))
+                }
+                code => check_code(code)?,
+            }
+            if meta.is_null() {
+                return Err(CurlError::WebSocketError(
+                    "frame received without metadata".to_string(),
+                ));
+            }
+
+            let (flags, bytes_left) = unsafe { (WsFlags((*meta).flags as u32), (*meta).bytesleft) };
+            match self
+                .frames
+                .push(flags, &self.buffer[..received], bytes_left)?
+            {
+                Some(Message::Ping(payload)) => {
+                    if !self.close_sent {
+                        self.send_frame(&payload, WsFlags::PONG)?;
+                    }
+                    return Ok(Message::Ping(payload));
+                }
+                Some(Message::Close(frame)) => {
+                    self.answer_close(frame.as_ref());
+                    return Ok(Message::Close(frame));
+                }
+                Some(message) => return Ok(message),
+                None => {}
+            }
+        }
+    }
+
+    /// Answer the peer's close frame if we haven't sent ours
+    fn answer_close(&mut self, frame: Option<&CloseFrame>) {
+        if self.close_sent {
+            return;
+        }
+        // The peer may already have hung up
+        let _ = self.send_frame(&close_reply(frame), WsFlags::CLOSE);
+        self.close_sent = true;
+    }
+
+    /// Block until the connection's socket can be read, or written when
+    /// `write` is set
+    fn wait(&self, write: bool, deadline: Option<Instant>) -> Result<()> {
+        let timeout = match deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    return Err(CurlError::from_curl_code(
+                        curl_sys::CURLE_OPERATION_TIMEDOUT,
+                    ));
+                }
+                Some(left)
+            }
+            None => None,
+        };
+
+        let mut socket: curl_sys::curl_socket_t = curl_sys::CURL_SOCKET_BAD;
+        unsafe {
+            check_code(curl_sys::curl_easy_getinfo(
+                self.curl.raw_handle(),
+                ffi::CURLINFO_ACTIVESOCKET,
+                &mut socket,
+            ))?;
+        }
+        if socket == curl_sys::CURL_SOCKET_BAD {
+            return Err(CurlError::WebSocketError("connection is gone".to_string()));
+        }
+        poll_socket(socket, write, timeout)
+    }
+}
+
+impl Drop for WebSocket {
+    fn drop(&mut self) {
+        if self.close_sent || self.frames.close_received {
+            return;
+        }
+        // One attempt without waiting; the connection closes either way
+        let payload = CloseCode::GOING_AWAY.0.to_be_bytes();
+        let mut sent = 0;
+        unsafe {
+            ffi::curl_ws_send(
+                self.curl.raw_handle(),
+                payload.as_ptr() as *const c_void,
+                payload.len(),
+                &mut sent,
+                0,
+                WsFlags::CLOSE.0,
+            );
+        }
+    }
+}
+
+fn refused(status: i64) -> CurlError {
+    CurlError::WebSocketError(format!(
+        "server answered the handshake with status {}",
+        status
+    ))
+}
+
+/// Status code and reason of a close frame's payload, `None` for an empty one
+fn parse_close(payload: &[u8]) -> Result<Option<CloseFrame>> {
+    match payload.len() {
+        0 => Ok(None),
+        1 => Err(CurlError::WebSocketError(
+            "close frame with a truncated status code".to_string(),
+        )),
+        _ => Ok(Some(CloseFrame {
+            code: CloseCode(u16::from_be_bytes([payload[0], payload[1]])),
+            reason: String::from_utf8_lossy(&payload[2..]).into_owned(),
+        })),
+    }
+}
+
+/// Payload answering a close frame: its status code, echoed
+fn close_reply(frame: Option<&CloseFrame>) -> Vec<u8> {
+    frame
+        .map(|frame| frame.code.0.to_be_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+fn check_control_payload(payload: &[u8]) -> Result<()> {
+    if payload.len() > MAX_CONTROL_PAYLOAD {
+        return Err(CurlError::WebSocketError(format!(
+            "control frame payload of {} bytes is over the {} byte limit",
+            payload.len(),
+            MAX_CONTROL_PAYLOAD
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn poll_socket(
+    socket: curl_sys::curl_socket_t,
+    write: bool,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let mut fd = libc::pollfd {
+        fd: socket,
+        events: if write { libc::POLLOUT } else { libc::POLLIN },
+        revents: 0,
+    };
+    // Rounded up, so a deadline less than 1ms away doesn't spin
+    let timeout_ms = timeout.map_or(-1, |timeout| {
+        timeout
+            .as_nanos()
+            .div_ceil(1_000_000)
+            .min(libc::c_int::MAX as u128) as libc::c_int
+    });
+    if unsafe { libc::poll(&mut fd, 1, timeout_ms) } < 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(CurlError::IoError(err));
+        }
+    }
+    Ok(())
+}
+
+/// Without `poll`, check back on the socket every few milliseconds
+#[cfg(not(unix))]
+fn poll_socket(
+    _socket: curl_sys::curl_socket_t,
+    _write: bool,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let interval = Duration::from_millis(10);
+    std::thread::sleep(timeout.map_or(interval, |timeout| timeout.min(interval)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_failure_is_an_error() {
+        assert!(matches!(
+            WebSocket::connect("ws://127.0.0.1:1/"),
+            Err(CurlError::CurlCode { .. })
+        ));
+        assert!(check_control_payload(&[0; MAX_CONTROL_PAYLOAD]).is_ok());
+        assert!(matches!(
+            check_control_payload(&[0; MAX_CONTROL_PAYLOAD + 1]),
+            Err(CurlError::WebSocketError(_))
+        ));
+    }
+
+    /// Feed `(flags, data, bytes_left)` parts, collecting the messages
+    fn reassemble(parts: &[(WsFlags, &[u8], i64)]) -> (Vec<Message>, Reassembler) {
+        let mut frames = Reassembler::default();
+        let mut messages = Vec::new();
+        for &(flags, data, bytes_left) in parts {
+            if let Some(message) = frames.push(flags, data, bytes_left).unwrap() {
+                messages.push(message);
+            }
+        }
+        (messages, frames)
+    }
+
+    #[test]
+    fn test_fragments_are_reassembled_around_control_frames() {
+        let text_cont = WsFlags(WsFlags::TEXT.0 | WsFlags::CONT.0);
+        let (messages, frames) = reassemble(&[
+            // First fragment, itself split across two reads
+            (text_cont, b"hel", 2),
+            (text_cont, b"lo", 0),
+            // A ping between fragments, its payload split too
+            (WsFlags::PING, b"pi", 2),
+            (WsFlags::PING, b"ng", 0),
+            (text_cont, b", wor", 0),
+            // Last fragment
+            (WsFlags::TEXT, b"ld", 0),
+            (WsFlags::BINARY, &[0, 159, 146, 150], 0),
+        ]);
+        assert_eq!(
+            messages,
+            vec![
+                Message::Ping(b"ping".to_vec()),
+                Message::Text("hello, world".to_string()),
+                Message::Binary(vec![0, 159, 146, 150]),
+            ]
+        );
+        assert!(frames.message.is_empty() && frames.control.is_empty());
+        assert!(!frames.close_received);
+    }
+
+    #[test]
+    fn test_text_must_be_utf8() {
+        let mut frames = Reassembler::default();
+        let text_cont = WsFlags(WsFlags::TEXT.0 | WsFlags::CONT.0);
+        // A code point split between fragments is fine
+        assert_eq!(frames.push(text_cont, &[0xe2, 0x82], 0).unwrap(), None);
+        assert_eq!(
+            frames.push(WsFlags::TEXT, &[0xac], 0).unwrap(),
+            Some(Message::Text("€".to_string()))
+        );
+        assert!(matches!(
+            frames.push(WsFlags::TEXT, &[b'a', 0xff], 0),
+            Err(CurlError::WebSocketError(_))
+        ));
+    }
+
+    #[test]
+    fn test_close_codes_are_parsed_and_echoed() {
+        let (messages, frames) = reassemble(&[
+            (WsFlags::CLOSE, &[0x03], 4),
+            (WsFlags::CLOSE, &[0xe9, b'b', b'y', b'e'], 0),
+        ]);
+        let frame = CloseFrame {
+            code: CloseCode::GOING_AWAY,
+            reason: "bye".to_string(),
+        };
+        assert_eq!(messages, vec![Message::Close(Some(frame.clone()))]);
+        assert!(frames.close_received);
+        assert_eq!(close_reply(Some(&frame)), vec![0x03, 0xe9]);
+
+        let (messages, _) = reassemble(&[(WsFlags::CLOSE, b"", 0)]);
+        assert_eq!(messages, vec![Message::Close(None)]);
+        assert_eq!(close_reply(None), Vec::<u8>::new());
+
+        let mut frames = Reassembler::default();
+        assert!(matches!(
+            frames.push(WsFlags::CLOSE, &[0x03], 0),
+            Err(CurlError::WebSocketError(_))
+        ));
+    }
+}
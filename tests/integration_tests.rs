@@ -2,15 +2,15 @@
 //!
 //! These tests make actual HTTP requests to httpbin.org
 
-use hyprcurl::{get, post, Browser, Curl, Request};
+use hyprcurl::{get, post, Browser, Curl, Request, Session};
 
 #[test]
 fn test_simple_get_request() {
     let response = get("https://httpbin.org/get").expect("GET request should succeed");
 
-    assert!(!response.is_empty(), "Response should not be empty");
+    assert!(!response.bytes().is_empty(), "Response should not be empty");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("httpbin"),
         "Response should contain 'httpbin'"
@@ -23,7 +23,7 @@ fn test_get_with_request_builder() {
         .send()
         .expect("GET request should succeed");
 
-    assert!(!response.is_empty(), "Response should not be empty");
+    assert!(!response.bytes().is_empty(), "Response should not be empty");
 }
 
 #[test]
@@ -31,9 +31,9 @@ fn test_simple_post_request() {
     let data = r#"{"test": "value"}"#;
     let response = post("https://httpbin.org/post", data).expect("POST request should succeed");
 
-    assert!(!response.is_empty(), "Response should not be empty");
+    assert!(!response.bytes().is_empty(), "Response should not be empty");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(body.contains("test"), "Response should contain posted data");
 }
 
@@ -44,7 +44,7 @@ fn test_post_with_request_builder() {
         .send()
         .expect("POST request should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("hello"),
         "Response should contain posted message"
@@ -58,7 +58,7 @@ fn test_get_with_browser_impersonation() {
         .send()
         .expect("Request with browser impersonation should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("Chrome"),
         "Response should show Chrome user agent"
@@ -72,7 +72,7 @@ fn test_get_with_firefox_impersonation() {
         .send()
         .expect("Request with Firefox impersonation should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("Firefox"),
         "Response should show Firefox user agent"
@@ -86,7 +86,7 @@ fn test_get_with_safari_impersonation() {
         .send()
         .expect("Request with Safari impersonation should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("Safari"),
         "Response should show Safari user agent"
@@ -188,7 +188,7 @@ fn test_http_proxy() {
 
     // This will fail if no proxy is running
     if let Ok(resp) = response {
-        assert!(!resp.is_empty(), "Response should not be empty");
+        assert!(!resp.bytes().is_empty(), "Response should not be empty");
     }
 }
 
@@ -201,7 +201,7 @@ fn test_socks_proxy() {
 
     // This will fail if no proxy is running
     if let Ok(resp) = response {
-        assert!(!resp.is_empty(), "Response should not be empty");
+        assert!(!resp.bytes().is_empty(), "Response should not be empty");
     }
 }
 
@@ -217,8 +217,32 @@ fn test_handle_reuse() {
         .send()
         .expect("Second request should succeed");
 
-    assert!(!response1.is_empty(), "First response should not be empty");
-    assert!(!response2.is_empty(), "Second response should not be empty");
+    assert!(
+        !response1.bytes().is_empty(),
+        "First response should not be empty"
+    );
+    assert!(
+        !response2.bytes().is_empty(),
+        "Second response should not be empty"
+    );
+}
+
+#[test]
+fn test_session_keeps_cookies() {
+    let session = Session::new().expect("Session should initialize");
+    session
+        .get("https://httpbin.org/cookies/set?session=abc")
+        .expect("Request should succeed");
+
+    let response = Request::get("https://httpbin.org/cookies")
+        .header("X-Trace", "1")
+        .send_with(&session)
+        .expect("Request should succeed");
+    assert!(
+        response.text().unwrap().contains("\"session\": \"abc\""),
+        "Cookie should be sent back by the session"
+    );
+    assert_eq!(session.cookies().get("session").unwrap().value, "abc");
 }
 
 #[test]
@@ -230,7 +254,7 @@ fn test_post_with_browser_and_json() {
         .send()
         .expect("POST with browser impersonation should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(
         body.contains("curl-cffi-rs"),
         "Response should contain posted data"
@@ -257,3 +281,179 @@ fn test_timeout_works() {
     // Request should fail with timeout
     assert!(result.is_err(), "Request should fail due to timeout");
 }
+
+#[test]
+fn test_response_metadata() {
+    let response = Request::get("https://httpbin.org/redirect/2")
+        .send()
+        .expect("Request should succeed");
+
+    assert_eq!(response.status(), 200, "Status code should be 200");
+    assert!(response.is_success());
+    assert!(
+        response.url().ends_with("/get"),
+        "Effective URL should point at the redirect target"
+    );
+    assert_eq!(response.header("content-type"), Some("application/json"));
+    assert!(response.http_version().is_some());
+    assert!(response.timings().total > std::time::Duration::ZERO);
+    assert!(response
+        .text()
+        .expect("Body should be UTF-8")
+        .contains("httpbin"));
+}
+
+#[test]
+fn test_repeated_response_headers() {
+    let response = Request::get("https://httpbin.org/response-headers?X-Multi=a&X-Multi=b")
+        .send()
+        .expect("Request should succeed");
+
+    let values: Vec<&str> = response.headers().get_all("x-multi").collect();
+    assert_eq!(values, ["a", "b"], "Both header values should be kept");
+}
+
+#[test]
+fn test_redirect_history() {
+    let response = Request::get("https://httpbin.org/redirect/2")
+        .send()
+        .expect("Request should succeed");
+
+    let history = response.history();
+    assert_eq!(history.len(), 2, "Both redirect hops should be recorded");
+    assert!(history[0].url().ends_with("/redirect/2"));
+    assert_eq!(history[0].status(), 302);
+    assert!(history[1].location().is_some_and(|l| l.ends_with("/get")));
+    assert!(response.url().ends_with("/get"));
+}
+
+#[test]
+fn test_error_for_status() {
+    let response = Request::get("https://httpbin.org/status/503")
+        .send()
+        .expect("Request should succeed");
+
+    assert_eq!(response.status(), 503);
+    match response.error_for_status() {
+        Err(hyprcurl::CurlError::HttpStatus { status, .. }) => assert_eq!(status, 503),
+        other => panic!(
+            "Expected HttpStatus error, got {:?}",
+            other.map(|r| r.status())
+        ),
+    }
+}
+
+#[test]
+fn test_perform_into_sink() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    curl.set_url("https://httpbin.org/bytes/4096")
+        .expect("Failed to set URL");
+
+    let mut sink = std::io::Cursor::new(Vec::new());
+    curl.perform_into(&mut sink)
+        .expect("Request should succeed");
+    assert_eq!(
+        sink.into_inner().len(),
+        4096,
+        "Sink should receive the whole body"
+    );
+}
+
+#[test]
+fn test_response_stream() {
+    let stream = Request::get("https://httpbin.org/stream/5")
+        .send_stream()
+        .expect("Streaming request should succeed");
+    assert_eq!(stream.status().expect("Status should be known"), 200);
+
+    let body: Vec<u8> = stream
+        .flat_map(|chunk| chunk.expect("Chunk should arrive"))
+        .collect();
+    let text = String::from_utf8_lossy(&body);
+    assert_eq!(text.lines().count(), 5, "Should stream 5 JSON lines");
+}
+
+#[cfg(all(feature = "async-tokio", unix))]
+#[tokio::test]
+async fn test_async_client_concurrent_requests() {
+    let client = hyprcurl::Client::new().expect("Failed to create client");
+
+    let tasks: Vec<_> = (0..5)
+        .map(|i| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                let request = Request::get(format!("https://httpbin.org/anything/{}", i))
+                    .impersonate(Browser::ChromeLatest);
+                client.send(request).await
+            })
+        })
+        .collect();
+    for (i, task) in tasks.into_iter().enumerate() {
+        let response = task
+            .await
+            .expect("Task should not panic")
+            .expect("Async request should succeed");
+        assert_eq!(response.status(), 200);
+        let body = String::from_utf8_lossy(response.bytes());
+        assert!(body.contains(&format!("/anything/{}", i)));
+    }
+}
+
+#[test]
+fn test_multi_runs_transfers_concurrently() {
+    let mut multi = hyprcurl::Multi::new().expect("Failed to create multi handle");
+    let ids: Vec<_> = (0..3)
+        .map(|i| {
+            multi
+                .add_request(Request::get(format!("https://httpbin.org/delay/1?i={}", i)))
+                .expect("Failed to add request")
+        })
+        .collect();
+
+    let start = std::time::Instant::now();
+    let completed = multi.run().expect("Multi should run");
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(3),
+        "Transfers should overlap"
+    );
+    assert_eq!(completed.len(), ids.len());
+    for completed in completed {
+        assert!(ids.contains(&completed.id));
+        let response = completed.result.expect("Request should succeed");
+        assert_eq!(response.status(), 200);
+    }
+}
+
+#[test]
+fn test_batch_results_in_input_order() {
+    let requests = (0..4).map(|i| Request::get(format!("https://httpbin.org/anything/{}", i)));
+    let options = hyprcurl::BatchOptions {
+        concurrency: 2,
+        per_host_limit: 2,
+    };
+
+    let results = hyprcurl::batch(requests, options);
+    assert_eq!(results.len(), 4);
+    for (i, result) in results.into_iter().enumerate() {
+        let response = result.expect("Request should succeed");
+        assert!(response.url().ends_with(&format!("/anything/{}", i)));
+    }
+}
+
+#[test]
+fn test_abort_handle_cancels_request() {
+    let mut request = Request::get("https://httpbin.org/delay/10");
+    let abort = request.abort_handle();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        abort.abort();
+    });
+
+    let start = std::time::Instant::now();
+    let result = request.send();
+    assert!(
+        matches!(result, Err(hyprcurl::CurlError::Cancelled)),
+        "Request should be cancelled"
+    );
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
@@ -1,6 +1,6 @@
 //! Tests for the Request builder API
 
-use hyprcurl::{Browser, Request};
+use hyprcurl::{Browser, Multipart, Part, PostRedirect, RedirectPolicy, Request};
 
 #[test]
 fn test_request_builder_simple_get() {
@@ -8,7 +8,7 @@ fn test_request_builder_simple_get() {
         .send()
         .expect("Simple GET should succeed");
 
-    assert!(!response.is_empty());
+    assert!(!response.bytes().is_empty());
 }
 
 #[test]
@@ -18,7 +18,7 @@ fn test_request_builder_get_with_impersonate() {
         .send()
         .expect("GET with impersonate should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(body.contains("Chrome"));
 }
 
@@ -30,7 +30,7 @@ fn test_request_builder_get_with_proxy() {
         .send();
 
     if let Ok(resp) = response {
-        assert!(!resp.is_empty());
+        assert!(!resp.bytes().is_empty());
     }
 }
 
@@ -43,7 +43,7 @@ fn test_request_builder_full_chain() {
         .send();
 
     if let Ok(resp) = response {
-        let body = String::from_utf8_lossy(&resp);
+        let body = String::from_utf8_lossy(resp.bytes());
         assert!(body.contains("Chrome"));
     }
 }
@@ -55,7 +55,7 @@ fn test_request_builder_post_simple() {
         .send()
         .expect("POST should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(body.contains("test"));
     assert!(body.contains("data"));
 }
@@ -68,7 +68,7 @@ fn test_request_builder_post_with_impersonate() {
         .send()
         .expect("POST with impersonate should succeed");
 
-    let body = String::from_utf8_lossy(&response);
+    let body = String::from_utf8_lossy(response.bytes());
     assert!(body.contains("hello"));
     assert!(body.contains("Firefox"));
 }
@@ -82,7 +82,7 @@ fn test_request_builder_post_with_proxy() {
         .send();
 
     if let Ok(resp) = response {
-        let body = String::from_utf8_lossy(&resp);
+        let body = String::from_utf8_lossy(resp.bytes());
         assert!(body.contains("key"));
     }
 }
@@ -97,12 +97,47 @@ fn test_request_builder_post_full_chain() {
         .send();
 
     if let Ok(resp) = response {
-        let body = String::from_utf8_lossy(&resp);
+        let body = String::from_utf8_lossy(resp.bytes());
         assert!(body.contains("curl-cffi-rs"));
         assert!(body.contains("Chrome"));
     }
 }
 
+#[test]
+fn test_request_builder_methods() {
+    let response = Request::put("https://httpbin.org/put", r#"{"kind": "put"}"#)
+        .send()
+        .expect("PUT should succeed");
+    assert_eq!(response.status(), 200);
+    assert!(String::from_utf8_lossy(response.bytes()).contains("put"));
+
+    let response = Request::patch("https://httpbin.org/patch", "patched")
+        .send()
+        .expect("PATCH should succeed");
+    assert_eq!(response.status(), 200);
+
+    let response = Request::delete("https://httpbin.org/delete")
+        .send()
+        .expect("DELETE should succeed");
+    assert_eq!(response.status(), 200);
+
+    let response = Request::head("https://httpbin.org/get")
+        .send()
+        .expect("HEAD should succeed");
+    assert_eq!(response.status(), 200);
+    assert!(response.bytes().is_empty(), "HEAD response has no body");
+}
+
+#[test]
+fn test_request_builder_custom_method() {
+    let response = Request::new("PURGE", "https://httpbin.org/anything")
+        .send()
+        .expect("Custom method should succeed");
+
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(body.contains("PURGE"));
+}
+
 #[test]
 fn test_builder_with_different_browsers() {
     let browsers = vec![
@@ -120,7 +155,7 @@ fn test_builder_with_different_browsers() {
             .send()
             .expect("Request should succeed for all browsers");
 
-        assert!(!response.is_empty(), "Response should not be empty");
+        assert!(!response.bytes().is_empty(), "Response should not be empty");
     }
 }
 
@@ -135,8 +170,8 @@ fn test_builder_url_types() {
         .send()
         .expect("String URL should work");
 
-    assert!(!response1.is_empty());
-    assert!(!response2.is_empty());
+    assert!(!response1.bytes().is_empty());
+    assert!(!response2.bytes().is_empty());
 }
 
 #[test]
@@ -152,8 +187,153 @@ fn test_builder_data_types() {
         .send()
         .expect("String data should work");
 
-    assert!(!response1.is_empty());
-    assert!(!response2.is_empty());
+    assert!(!response1.bytes().is_empty());
+    assert!(!response2.bytes().is_empty());
+}
+
+#[test]
+fn test_builder_binary_body() {
+    let payload: Vec<u8> = vec![0x00, 0x01, 0x02, 0x00, 0xff];
+    let response = Request::post("https://httpbin.org/post", payload)
+        .send()
+        .expect("Binary POST should succeed");
+
+    // httpbin reports non-UTF-8 bodies as a base64 data URL
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(
+        body.contains("AAECAP8="),
+        "Body should round-trip byte-for-byte"
+    );
+}
+
+#[test]
+fn test_builder_streamed_body() {
+    let payload = std::io::Cursor::new(b"streamed-payload".to_vec());
+    let response = Request::new("PUT", "https://httpbin.org/put")
+        .body_seekable(payload, Some(16))
+        .send()
+        .expect("Streamed PUT should succeed");
+
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(body.contains("streamed-payload"));
+}
+
+#[test]
+fn test_builder_multipart() {
+    let form = Multipart::new().text("title", "holiday").part(
+        Part::bytes("photo", b"fake-png".to_vec())
+            .file_name("beach.png")
+            .content_type("image/png"),
+    );
+    let response = Request::new("POST", "https://httpbin.org/post")
+        .multipart(form)
+        .send()
+        .expect("Multipart POST should succeed");
+
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(
+        body.contains("\"title\": \"holiday\""),
+        "Text field missing"
+    );
+    assert!(body.contains("fake-png"), "File part missing");
+}
+
+#[test]
+fn test_builder_query_params() {
+    let response = Request::get("https://httpbin.org/get?lang=en")
+        .query(&[("q", "rust curl"), ("sym", "a&b=c")])
+        .send()
+        .expect("GET with query should succeed");
+
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(body.contains("\"lang\": \"en\""), "Existing query lost");
+    assert!(body.contains("\"q\": \"rust curl\""), "Query not encoded");
+    assert!(body.contains("\"sym\": \"a&b=c\""), "Query not escaped");
+}
+
+#[test]
+fn test_builder_form() {
+    let response = Request::new("POST", "https://httpbin.org/post")
+        .form(&[("user", "jane doe"), ("note", "50%+")])
+        .send()
+        .expect("Form POST should succeed");
+
+    let body = String::from_utf8_lossy(response.bytes());
+    assert!(body.contains("\"user\": \"jane doe\""));
+    assert!(body.contains("\"note\": \"50%+\""));
+    assert!(body.contains("application/x-www-form-urlencoded"));
+}
+
+#[test]
+fn test_builder_redirect_policies() {
+    let response = Request::get("https://httpbin.org/redirect/2")
+        .redirect_policy(RedirectPolicy::None)
+        .send()
+        .expect("Request should succeed");
+    assert_eq!(response.status(), 302);
+
+    let result = Request::get("https://httpbin.org/redirect/3")
+        .redirect_policy(RedirectPolicy::Limited(1))
+        .send();
+    assert!(result.is_err(), "Exceeding the limit should fail");
+
+    // https -> http is another origin
+    let cross = "https://httpbin.org/redirect-to?url=http%3A%2F%2Fhttpbin.org%2Fheaders";
+    let response = Request::get(cross)
+        .redirect_policy(RedirectPolicy::SameOrigin(5))
+        .send()
+        .expect("Request should succeed");
+    assert_eq!(
+        response.status(),
+        302,
+        "Cross-origin hop should not be followed"
+    );
+
+    let response = Request::get(cross)
+        .redirect_policy(RedirectPolicy::custom(|attempt| attempt.previous() < 5))
+        .header("Authorization", "Bearer secret")
+        .header("X-Keep", "yes")
+        .send()
+        .expect("Request should succeed");
+    let body = response.text().unwrap();
+    assert_eq!(response.history().len(), 1);
+    assert!(
+        !body.contains("Bearer secret"),
+        "Authorization must be stripped"
+    );
+    assert!(body.contains("X-Keep"));
+}
+
+#[test]
+fn test_builder_post_redirect() {
+    let url = "https://httpbin.org/redirect-to?url=%2Fanything&status_code=302";
+    let response = Request::post(url, "data")
+        .send()
+        .expect("Request should succeed");
+    assert!(response.text().unwrap().contains("\"method\": \"GET\""));
+
+    let response = Request::post(url, "data")
+        .post_redirect(PostRedirect {
+            on_302: true,
+            ..PostRedirect::default()
+        })
+        .redirect_policy(RedirectPolicy::SameOrigin(5))
+        .send()
+        .expect("Request should succeed");
+    assert!(response.text().unwrap().contains("\"method\": \"POST\""));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_builder_json_roundtrip() {
+    let response = Request::new("POST", "https://httpbin.org/post")
+        .json(&serde_json::json!({"name": "hyprcurl", "tags": ["http"]}))
+        .send()
+        .expect("JSON POST should succeed");
+
+    let echo: serde_json::Value = response.json().expect("httpbin returns JSON");
+    assert_eq!(echo["json"]["name"], "hyprcurl");
+    assert_eq!(echo["headers"]["Content-Type"], "application/json");
 }
 
 #[test]
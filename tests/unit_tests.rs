@@ -1,6 +1,9 @@
 //! Unit tests for curl-cffi-rs
 
-use hyprcurl::{Browser, Curl, CurlOpt};
+use hyprcurl::{Browser, Curl, CurlOpt, HeaderMap, Method, Multipart, Part, TlsVersion};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
 
 #[test]
 fn test_curl_initialization() {
@@ -42,6 +45,23 @@ fn test_add_header() {
     assert!(result.is_ok(), "Adding multiple headers should succeed");
 }
 
+#[test]
+fn test_header_map_on_curl() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    curl.add_header("X-One: 1").expect("Failed to add header");
+    assert!(
+        curl.add_header("X-Evil: a\r\nHost: attacker").is_err(),
+        "Header injection should be rejected"
+    );
+    assert!(curl.add_header("missing colon").is_err());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("Accept", "application/json").unwrap();
+    curl.set_headers(headers);
+    assert_eq!(curl.headers().get("accept"), Some("application/json"));
+    assert!(!curl.headers().contains_key("X-One"));
+}
+
 #[test]
 fn test_set_timeout() {
     let mut curl = Curl::new().expect("Failed to initialize curl");
@@ -162,6 +182,37 @@ fn test_browser_tls_curves() {
     );
 }
 
+#[test]
+fn test_browser_http2_fingerprint() {
+    let firefox = Browser::FirefoxLatest.http2_fingerprint();
+    assert_eq!(
+        firefox.akamai(),
+        "1:65536;4:131072;5:16384|12517377|3:0:0:201,5:0:0:101,7:0:0:1,9:0:7:1,11:0:3:1,13:0:0:241|m,p,a,s"
+    );
+    assert_eq!(
+        Browser::EdgeLatest.http2_fingerprint(),
+        Browser::ChromeLatest.http2_fingerprint()
+    );
+
+    let safari = Browser::SafariLatest.http2_fingerprint();
+    assert!(safari.akamai().ends_with("|10420225|0|m,s,a,p"));
+}
+
+#[test]
+fn test_tls_version_option() {
+    assert_eq!(CurlOpt::SslCurves.to_raw(), 10298);
+    assert_eq!(
+        TlsVersion::V1_2.to_curl() | TlsVersion::V1_3.to_curl_max(),
+        6 | 7 << 16
+    );
+
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    let (min, max) = Browser::SafariLatest.tls_versions();
+    assert!(min < max);
+    assert!(curl.set_tls_version(min, Some(max)).is_ok());
+    assert!(curl.set_tls_version(TlsVersion::V1_3, None).is_ok());
+}
+
 #[test]
 fn test_post_data() {
     let mut curl = Curl::new().expect("Failed to initialize curl");
@@ -169,6 +220,59 @@ fn test_post_data() {
     assert!(result.is_ok(), "Setting POST data should succeed");
 }
 
+#[test]
+fn test_post_bytes_with_nul() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    let result = curl.set_post_bytes(&[0x08, 0x00, 0x96, 0x01]);
+    assert!(
+        result.is_ok(),
+        "Binary POST data with NUL bytes should be accepted"
+    );
+
+    let result = curl.set_body(vec![0u8; 16]);
+    assert!(result.is_ok(), "Owned binary body should be accepted");
+}
+
+#[test]
+fn test_body_reader() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    let result = curl.set_body_reader(std::io::repeat(0).take(1024), Some(1024));
+    assert!(result.is_ok(), "Setting a streamed body should succeed");
+
+    let result = curl.set_body_seekable(std::io::Cursor::new(vec![1u8; 64]), None);
+    assert!(
+        result.is_ok(),
+        "Setting a seekable body of unknown length should succeed"
+    );
+}
+
+#[test]
+fn test_multipart() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    let form = Multipart::new()
+        .text("title", "report")
+        .part(
+            Part::bytes("blob", vec![0u8, 1, 2])
+                .file_name("blob.bin")
+                .content_type("application/octet-stream"),
+        )
+        .reader("log", std::io::Cursor::new(b"line".to_vec()), None);
+    assert_eq!(form.len(), 3);
+    assert!(
+        curl.set_multipart(form).is_ok(),
+        "Setting a form should succeed"
+    );
+
+    // Replacing the form with a plain body must release it cleanly
+    assert!(curl.set_post_data("plain").is_ok());
+
+    let missing = Multipart::new().file("upload", "/nonexistent/hyprcurl.bin");
+    assert!(
+        curl.set_multipart(missing).is_err(),
+        "A missing file should be reported when the form is set"
+    );
+}
+
 #[test]
 fn test_reset() {
     let mut curl = Curl::new().expect("Failed to initialize curl");
@@ -184,3 +288,81 @@ fn test_reset() {
     let result = curl.set_url("https://httpbin.org/post");
     assert!(result.is_ok(), "Should be able to set URL after reset");
 }
+
+#[test]
+fn test_method_from_str() {
+    assert_eq!(Method::from("get"), Method::Get);
+    assert_eq!(Method::from("DELETE"), Method::Delete);
+    assert_eq!(Method::from("PURGE"), Method::Custom("PURGE".to_string()));
+    assert_eq!(Method::Patch.as_str(), "PATCH");
+    assert_eq!(Method::Custom("PURGE".to_string()).to_string(), "PURGE");
+}
+
+#[test]
+fn test_set_method() {
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    for method in [
+        Method::Get,
+        Method::Head,
+        Method::Post,
+        Method::Put,
+        Method::Custom("PURGE".to_string()),
+    ] {
+        assert!(
+            curl.set_method(&method).is_ok(),
+            "Setting {} should succeed",
+            method
+        );
+    }
+}
+
+/// Answer one request with an empty 200 and return the raw request
+fn capture_request() -> (String, thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(head_end) = text.find("\r\n\r\n") {
+                let len = text[..head_end]
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .map_or(0, |len| len.parse().unwrap());
+                if request.len() >= head_end + 4 + len {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (url, server)
+}
+
+#[test]
+fn test_set_method_keeps_get_body() {
+    let (url, server) = capture_request();
+    let mut curl = Curl::new().expect("Failed to initialize curl");
+    curl.set_url(&url).unwrap();
+    curl.set_post_data("hello").unwrap();
+    curl.set_method(&Method::Get).unwrap();
+    curl.perform(&mut Vec::new()).unwrap();
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET / HTTP/1.1\r\n"), "{}", request);
+    assert!(request.contains("Content-Length: 5\r\n"), "{}", request);
+    assert!(request.ends_with("\r\n\r\nhello"), "{}", request);
+
+    // HEAD would silently drop it
+    assert!(matches!(
+        curl.set_method(&Method::Head),
+        Err(hyprcurl::CurlError::InvalidOption(_))
+    ));
+}